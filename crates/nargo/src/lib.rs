@@ -11,5 +11,6 @@ pub mod artifacts;
 mod errors;
 pub mod manifest;
 pub mod ops;
+pub mod workspace;
 
 pub use self::errors::NargoError;
@@ -9,13 +9,17 @@ pub enum InvalidPackageError {
     MissingManifestFile(PathBuf),
 
     /// Package manifest is unreadable.
-    #[error("Nargo.toml is badly formed, could not parse.\n\n {0}")]
-    MalformedManifestFile(#[from] toml::de::Error),
+    #[error("Nargo.toml is badly formed, could not parse.\n\n{0}")]
+    MalformedManifestFile(#[from] ManifestParseError),
 
     /// Package does not contain Noir source files.
     #[error("cannot find src directory in path {}", .0.display())]
     NoSourceDir(PathBuf),
 
+    /// Package source file or directory could not be read.
+    #[error("cannot read {}", .0.display())]
+    UnreadableSource(PathBuf),
+
     /// Package has neither of `main.nr` and `lib.nr`.
     #[error("package must contain either a `lib.nr`(Library) or a `main.nr`(Binary).")]
     ContainsZeroCrates,
@@ -23,4 +27,140 @@ pub enum InvalidPackageError {
     /// Package has both a `main.nr` (for binaries) and `lib.nr` (for libraries)
     #[error("package cannot contain both a `lib.nr` and a `main.nr`")]
     ContainsMultipleCrates,
+
+    /// Package's `min_nargo` is not a valid semantic version.
+    #[error("`min_nargo` version `{0}` is not a valid semantic version")]
+    MalformedNargoVersion(String),
+
+    /// Package requires a newer version of nargo than the one running.
+    #[error("package requires nargo {required} or newer, but the current version is {current}")]
+    NargoTooOld { required: semver::Version, current: semver::Version },
+
+    /// Package declares a workspace without any members.
+    #[error("`[workspace]` must list at least one member")]
+    EmptyWorkspace,
+
+    /// Package declares a build script which does not exist.
+    #[error("cannot find build script {}", .0.display())]
+    MissingBuildScript(PathBuf),
+
+    /// Package's `default-run` entry does not exist.
+    #[error("cannot find default-run entry {}", .0.display())]
+    MissingDefaultRun(PathBuf),
+
+    /// Package inherits a metadata field which its workspace does not define.
+    #[error("`{0}` is inherited from the workspace, but `[workspace.package]` does not define it")]
+    MissingWorkspaceField(&'static str),
+
+    /// Package metadata contains a URL which cannot be parsed.
+    #[error("`{field}` is not a valid URL: {url}")]
+    MalformedUrl { field: &'static str, url: String },
+
+    /// Git dependency's `git` field is not an http(s) or ssh URL.
+    #[error("`git` is not a valid http(s) or ssh URL: {0}")]
+    MalformedGitUrl(String),
+
+    /// Workspace member resolves to a directory outside of the workspace root.
+    #[error("workspace member `{0}` is outside of the workspace root")]
+    MemberOutsideWorkspace(String),
+
+    /// Package requests a language edition which this version of nargo does not know.
+    #[error("unknown edition `{0}`")]
+    UnknownEdition(String),
+
+    /// Package keyword contains characters other than lowercase alphanumerics and dashes.
+    #[error("keyword `{0}` must only contain lowercase alphanumeric characters and dashes")]
+    InvalidKeyword(String),
+
+    /// Package lists more keywords than a registry would accept.
+    #[error("package lists {0} keywords, but at most {} are allowed", super::MAX_KEYWORDS)]
+    TooManyKeywords(usize),
+
+    /// The same dependency name refers to different sources within the dependency tree.
+    #[error("dependency `{0}` is required from conflicting sources")]
+    ConflictingDependencyVersions(String),
+
+    /// Git dependency has a path dependency, which may not be available once it is fetched.
+    #[error("remote(git) dependency has a local dependency `{0}`")]
+    RemoteDepWithLocalDep(String),
+
+    /// Workspace sets a lint to a level other than `allow`, `warn` or `deny`.
+    #[error(
+        "lint `{lint}` has unknown level `{level}`, expected one of `allow`, `warn` or `deny`"
+    )]
+    UnknownLintLevel { lint: String, level: String },
+
+    /// Local packages depend on each other through their normal dependencies.
+    #[error("packages {} depend on each other", display_packages(.0))]
+    DependencyCycle(Vec<PathBuf>),
+}
+
+/// Lists package directories for an error message, e.g. "`a`, `b`".
+fn display_packages(packages: &[PathBuf]) -> String {
+    let packages: Vec<_> =
+        packages.iter().map(|package| format!("`{}`", package.display())).collect();
+    packages.join(", ")
+}
+
+/// A TOML error encountered while parsing a package manifest, along with where it occurred.
+#[derive(Debug)]
+pub struct ManifestParseError {
+    /// Description of the problem as reported by the TOML parser.
+    pub message: String,
+    /// Location of the problem, if the TOML parser was able to report one.
+    pub location: Option<ManifestLocation>,
+}
+
+/// A position within a package manifest, used to point at the cause of a parse error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestLocation {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column number, counted in characters.
+    pub column: usize,
+    /// The full contents of the offending line.
+    pub snippet: String,
+}
+
+impl ManifestParseError {
+    /// Attaches a line and column to `error` by resolving its span against `toml_as_string`,
+    /// the source which failed to parse.
+    pub(crate) fn new(error: toml::de::Error, toml_as_string: &str) -> Self {
+        let location = error.span().map(|span| {
+            let offset = span.start.min(toml_as_string.len());
+            let preceding = &toml_as_string[..offset];
+
+            let line_start = preceding.rfind('\n').map_or(0, |index| index + 1);
+            let line = preceding.matches('\n').count() + 1;
+            let column = preceding[line_start..].chars().count() + 1;
+            let snippet =
+                toml_as_string[line_start..].lines().next().unwrap_or_default().to_owned();
+
+            ManifestLocation { line, column, snippet }
+        });
+
+        ManifestParseError { message: error.message().trim_end().to_owned(), location }
+    }
 }
+
+impl std::fmt::Display for ManifestParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ManifestLocation { line, column, snippet } = match &self.location {
+            Some(location) => location,
+            None => return write!(f, "{}", self.message),
+        };
+
+        // Render the offending line with a caret underneath, e.g.
+        //  --> Nargo.toml:3:11
+        //   |
+        // 3 | authors = [
+        //   |           ^ invalid array
+        let gutter = " ".repeat(line.to_string().len());
+        writeln!(f, "{gutter}--> Nargo.toml:{line}:{column}")?;
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line} | {snippet}")?;
+        write!(f, "{gutter} | {}^ {}", " ".repeat(column - 1), self.message)
+    }
+}
+
+impl std::error::Error for ManifestParseError {}
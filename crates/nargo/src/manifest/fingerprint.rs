@@ -0,0 +1,52 @@
+//! Stable fingerprints of a package's sources, used to tell whether it must be rebuilt.
+use std::path::{Path, PathBuf};
+
+use super::InvalidPackageError;
+
+/// An incremental 64-bit FNV-1a hash. Unlike `DefaultHasher`, its output is fixed across Rust
+/// releases and platforms, so fingerprints may be stored and compared between builds.
+pub(super) struct Fingerprint(u64);
+
+impl Fingerprint {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub(super) fn new() -> Self {
+        Fingerprint(Self::OFFSET_BASIS)
+    }
+
+    /// Feeds `bytes` into the fingerprint. Each write is prefixed with its length so that
+    /// moving bytes between consecutive writes changes the result.
+    pub(super) fn write(&mut self, bytes: &[u8]) {
+        let length = (bytes.len() as u64).to_le_bytes();
+        for byte in length.iter().chain(bytes) {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    pub(super) fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Returns every file within `dir` and its subdirectories, sorted by path.
+pub(super) fn collect_files(dir: &Path) -> Result<Vec<PathBuf>, InvalidPackageError> {
+    let mut files = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|_| InvalidPackageError::UnreadableSource(dir.clone()))?;
+        for entry in entries {
+            let path =
+                entry.map_err(|_| InvalidPackageError::UnreadableSource(dir.clone()))?.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -1,13 +1,48 @@
-use serde::Deserialize;
-use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
 
 mod errors;
-pub use self::errors::InvalidPackageError;
+mod fingerprint;
+mod schema;
+pub use self::errors::{InvalidPackageError, ManifestLocation, ManifestParseError};
+use self::fingerprint::{collect_files, Fingerprint};
 
-#[derive(Debug, Deserialize, Clone)]
+/// The name under which the standard library is implicitly depended upon.
+const STD_DEPENDENCY_NAME: &str = "std";
+
+/// The maximum number of keywords a package may list.
+pub const MAX_KEYWORDS: usize = 5;
+
+/// The feature which is enabled in a package unless opted out with `default-features = false`.
+pub const DEFAULT_FEATURE: &str = "default";
+
+/// The dependencies of a package, keyed by name and kept in the order they are written in the
+/// manifest.
+pub type DependencyMap = indexmap::IndexMap<String, Dependency>;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PackageManifest {
     pub package: PackageMetadata,
-    pub dependencies: BTreeMap<String, Dependency>,
+    pub dependencies: DependencyMap,
+    /// Dependencies only needed to build the package's tests, from `[dev-dependencies]`.
+    #[serde(rename = "dev-dependencies", default, skip_serializing_if = "DependencyMap::is_empty")]
+    pub dev_dependencies: DependencyMap,
+    pub workspace: Option<WorkspaceConfig>,
+    /// Paths from the `[artifacts]` table, relative to the package root.
+    #[serde(rename = "artifacts", default, skip_serializing_if = "ArtifactPaths::is_empty")]
+    pub artifact_paths: ArtifactPaths,
+    /// Compilation settings from each `[profile.<name>]` table.
+    #[serde(rename = "profile", default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Maps each feature of the package to the dependencies and other features it enables.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<String, Vec<String>>,
+    /// The order a backend should expect the public inputs in, from `[public-inputs]`.
+    #[serde(rename = "public-inputs", default, skip_serializing_if = "PublicInputs::is_empty")]
+    pub public_inputs: PublicInputs,
 }
 
 impl PackageManifest {
@@ -19,33 +54,839 @@ impl PackageManifest {
     }
 
     pub fn from_toml_str(toml_as_string: &str) -> Result<Self, InvalidPackageError> {
-        let manifest = toml::from_str::<PackageManifest>(toml_as_string)?;
+        let mut manifest = toml::from_str::<PackageManifest>(toml_as_string)
+            .map_err(|error| ManifestParseError::new(error, toml_as_string))?;
+        for dependency in
+            manifest.dependencies.values_mut().chain(manifest.dev_dependencies.values_mut())
+        {
+            dependency.validate()?;
+            *dependency = dependency.normalized();
+        }
+        if let Some(workspace) = &manifest.workspace {
+            workspace.validate()?;
+        }
+        manifest.package.validate_keywords()?;
+        manifest.package.validate_edition()?;
         Ok(manifest)
     }
+
+    /// Renders the manifest back into TOML. Dependencies are written in the order they were
+    /// parsed in.
+    pub fn to_toml_str(&self) -> String {
+        toml::to_string(self).expect("ice: a parsed manifest should always be representable")
+    }
+
+    /// Returns the path of the package's build script, if it declares one, resolved relative
+    /// to `root`, the directory containing the package's manifest.
+    pub fn build_script(&self, root: &Path) -> Option<PathBuf> {
+        self.package.build.as_ref().map(|build| root.join(build))
+    }
+
+    /// Returns the entry the package runs by default, if it declares one with `default-run`.
+    pub fn default_run(&self) -> Option<&str> {
+        self.package.default_run.as_deref()
+    }
+
+    /// Returns the artifact paths declared by the package, resolved relative to `root`, the
+    /// directory containing the package's manifest.
+    ///
+    /// Any artifact without a declared path is left as None so that the default location is used.
+    pub fn artifacts(&self, root: &Path) -> ArtifactPaths {
+        let resolve = |path: &Option<PathBuf>| path.as_ref().map(|path| root.join(path));
+        ArtifactPaths {
+            proving_key: resolve(&self.artifact_paths.proving_key),
+            verification_key: resolve(&self.artifact_paths.verification_key),
+            acir: resolve(&self.artifact_paths.acir),
+        }
+    }
+
+    /// Fills in each of the package's metadata fields which are unset, or which explicitly
+    /// request inheritance, from the shared `[workspace.package]` table of `workspace`.
+    ///
+    /// Errors if a field requests inheritance but the workspace does not define it.
+    pub fn inherit_metadata(
+        &mut self,
+        workspace: &WorkspaceConfig,
+    ) -> Result<(), InvalidPackageError> {
+        let shared = match &workspace.package {
+            Some(shared) => shared.clone(),
+            None => PackageMetadata::default(),
+        };
+
+        let package = &mut self.package;
+        inherit_field("authors", &mut package.authors, shared.authors)?;
+        inherit_field("compiler_version", &mut package.compiler_version, shared.compiler_version)?;
+        inherit_field("backend", &mut package.backend, shared.backend)?;
+        inherit_field("license", &mut package.license, shared.license)?;
+        Ok(())
+    }
+
+    /// Returns a copy of this manifest with `std_source` added as its `std` dependency, making
+    /// the implicit dependency on the standard library visible to tooling which walks
+    /// dependencies.
+    ///
+    /// The manifest is returned unchanged if it already declares a `std` dependency or if the
+    /// package opts out with `no_std = true`.
+    pub fn with_implicit_std(&self, std_source: Dependency) -> PackageManifest {
+        let mut manifest = self.clone();
+        if !self.package.no_std {
+            manifest.dependencies.entry(STD_DEPENDENCY_NAME.to_string()).or_insert(std_source);
+        }
+        manifest
+    }
+
+    /// Checks that the package's `repository`, `homepage` and `documentation`, if present, are
+    /// well-formed URLs.
+    pub fn validate_urls(&self) -> Result<(), InvalidPackageError> {
+        let fields = [
+            ("repository", &self.package.repository),
+            ("homepage", &self.package.homepage),
+            ("documentation", &self.package.documentation),
+        ];
+        for (field, value) in fields {
+            if let Some(value) = value {
+                url::Url::parse(value)
+                    .map_err(|_| InvalidPackageError::MalformedUrl { field, url: value.clone() })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the dependencies which are active when `enabled_features` are enabled: every
+    /// non-optional dependency, along with each optional dependency enabled by one of those
+    /// features or by a feature they in turn enable. Dependencies are returned in the order
+    /// they are written in the manifest.
+    pub fn active_dependencies(&self, enabled_features: &[String]) -> Vec<(&str, &Dependency)> {
+        let mut enabled = BTreeSet::new();
+        let mut stack: Vec<&str> = enabled_features.iter().map(String::as_str).collect();
+        while let Some(feature) = stack.pop() {
+            if enabled.insert(feature) {
+                if let Some(entries) = self.features.get(feature) {
+                    stack.extend(entries.iter().map(String::as_str));
+                }
+            }
+        }
+
+        self.dependencies
+            .iter()
+            .filter(|(name, dependency)| {
+                !dependency.is_optional() || enabled.contains(name.as_str())
+            })
+            .map(|(name, dependency)| (name.as_str(), dependency))
+            .collect()
+    }
+
+    /// Returns the names of optional dependencies which no feature enables, and so can never
+    /// be active.
+    pub fn unreachable_optional_dependencies(&self) -> Vec<&str> {
+        self.dependencies
+            .iter()
+            .filter(|(name, dependency)| {
+                dependency.is_optional()
+                    && !self.features.values().flatten().any(|entry| entry == *name)
+            })
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    /// Flattens the package's dependency tree into every dependency it transitively requires,
+    /// sorted by name. `fetch` loads the manifest of each dependency as it is reached.
+    ///
+    /// Only active dependencies are followed: the package's own `DEFAULT_FEATURE` is enabled,
+    /// and each dependency has the features it is required with enabled, unified across every
+    /// place it is required from. Path dependencies are rebased to be relative to this package,
+    /// both when passed to `fetch` and in the result.
+    ///
+    /// Each name is resolved once, so a name may not refer to different sources (git URLs,
+    /// tags, or paths) in different parts of the tree.
+    pub fn resolve_transitive(
+        &self,
+        fetch: impl Fn(&Dependency) -> Result<PackageManifest, InvalidPackageError>,
+    ) -> Result<Vec<(String, Dependency)>, InvalidPackageError> {
+        let mut resolved: BTreeMap<String, (Dependency, BTreeSet<String>)> = BTreeMap::new();
+        let mut manifests: BTreeMap<String, PackageManifest> = BTreeMap::new();
+        let mut stack =
+            self.rebased_active_dependencies(&[DEFAULT_FEATURE.to_string()], Some("."))?;
+
+        while let Some((name, dependency)) = stack.pop() {
+            let features = dependency.enabled_features();
+            match resolved.get_mut(&name) {
+                Some((existing, enabled)) => {
+                    if !existing.has_same_source(&dependency) {
+                        return Err(InvalidPackageError::ConflictingDependencyVersions(name));
+                    }
+                    if features.iter().all(|feature| enabled.contains(feature)) {
+                        continue;
+                    }
+                    // Revisit the dependency with the union of the features it is required with
+                    enabled.extend(features);
+                }
+                None => {
+                    manifests.insert(name.clone(), fetch(&dependency)?);
+                    resolved.insert(name.clone(), (dependency, features.into_iter().collect()));
+                }
+            }
+
+            let (dependency, enabled) = &resolved[&name];
+            let enabled: Vec<_> = enabled.iter().cloned().collect();
+            let base = match dependency {
+                Dependency::Path { path, .. } => Some(path.as_str()),
+                Dependency::Github { .. } => None,
+            };
+            stack.extend(manifests[&name].rebased_active_dependencies(&enabled, base)?);
+        }
+
+        Ok(resolved.into_iter().map(|(name, (dependency, _))| (name, dependency)).collect())
+    }
+
+    /// Returns the dependencies which are active when `enabled_features` are enabled, with each
+    /// path dependency's path rebased from `base`, the directory of this package relative to
+    /// the root package. `base` is None for a package fetched from git, which cannot have path
+    /// dependencies as there is no guarantee they are available.
+    fn rebased_active_dependencies(
+        &self,
+        enabled_features: &[String],
+        base: Option<&str>,
+    ) -> Result<Vec<(String, Dependency)>, InvalidPackageError> {
+        self.active_dependencies(enabled_features)
+            .into_iter()
+            .map(|(name, dependency)| {
+                let mut dependency = dependency.clone();
+                if let Dependency::Path { path, .. } = &mut dependency {
+                    let base = base.ok_or_else(|| {
+                        InvalidPackageError::RemoteDepWithLocalDep(name.to_string())
+                    })?;
+                    *path = rebase_dependency_path(base, path);
+                }
+                Ok((name.to_string(), dependency))
+            })
+            .collect()
+    }
+
+    /// Returns the compilation settings of the profile called `name`.
+    ///
+    /// If the manifest does not declare the profile, the default settings are returned.
+    pub fn profile(&self, name: &str) -> Profile {
+        self.profiles.get(name).cloned().unwrap_or_default()
+    }
+
+    /// Checks that the `default-run` entry declared by the package rooted at `root`, if any,
+    /// exists.
+    pub fn check_default_run(&self, root: &Path) -> Result<(), InvalidPackageError> {
+        match self.default_run().map(|default_run| root.join(default_run)) {
+            Some(default_run) if !default_run.is_file() => {
+                Err(InvalidPackageError::MissingDefaultRun(default_run))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a fingerprint of the package rooted at `root`, covering this manifest and the
+    /// path and contents of every file within the package's `src` directory.
+    ///
+    /// The fingerprint is stable across runs, so a build tool may store it and skip rebuilding
+    /// the package while it is unchanged. The manifest is fingerprinted in its canonical form,
+    /// so reformatting `Nargo.toml` alone does not change it.
+    pub fn source_fingerprint(&self, root: &Path) -> Result<u64, InvalidPackageError> {
+        let src_dir = root.join("src");
+        if !src_dir.is_dir() {
+            return Err(InvalidPackageError::NoSourceDir(root.to_path_buf()));
+        }
+
+        let mut fingerprint = Fingerprint::new();
+        fingerprint.write(self.to_toml_str().as_bytes());
+        for file in collect_files(&src_dir)? {
+            let contents = std::fs::read(&file)
+                .map_err(|_| InvalidPackageError::UnreadableSource(file.clone()))?;
+
+            // Spell the path the same way on every platform
+            let relative = file.strip_prefix(root).expect("ice: source file should be in root");
+            let components: Vec<_> = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect();
+            fingerprint.write(components.join("/").as_bytes());
+            fingerprint.write(&contents);
+        }
+        Ok(fingerprint.finish())
+    }
+
+    /// Checks that the build script declared by the package rooted at `root`, if any, exists.
+    pub fn check_build_script(&self, root: &Path) -> Result<(), InvalidPackageError> {
+        match self.build_script(root) {
+            Some(build_script) if !build_script.is_file() => {
+                Err(InvalidPackageError::MissingBuildScript(build_script))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct PackageMetadata {
     // Note: a package name is not needed unless there is a registry
-    authors: Vec<String>,
+    authors: Option<MaybeWorkspace<Vec<String>>>,
     // If not compiler version is supplied, the latest is used
     // For now, we state that all packages must be compiled under the same
     // compiler version.
     // We also state that ACIR and the compiler will upgrade in lockstep.
     // so you will not need to supply an ACIR and compiler version
-    compiler_version: Option<String>,
-    backend: Option<String>,
-    license: Option<String>,
+    compiler_version: Option<MaybeWorkspace<String>>,
+    backend: Option<MaybeWorkspace<String>>,
+    license: Option<MaybeWorkspace<String>>,
+    // The oldest version of nargo able to build this package
+    min_nargo: Option<String>,
+    // A script or code generator to run before compiling the package,
+    // relative to the package root
+    build: Option<PathBuf>,
+    // The source file of the circuit to run when the package contains several,
+    // relative to the package root
+    #[serde(rename = "default-run")]
+    default_run: Option<String>,
+    // Opts out of the implicit dependency on the standard library
+    #[serde(default, skip_serializing_if = "is_false")]
+    no_std: bool,
+    // Metadata for publishing the package, unused by the compiler
+    description: Option<String>,
+    readme: Option<PathBuf>,
+    repository: Option<String>,
+    homepage: Option<String>,
+    documentation: Option<String>,
+    // The language edition the package is written against
+    edition: Option<String>,
+    // Search terms and categories for a future package registry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    keywords: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    categories: Vec<String>,
+    // Arbitrary configuration for external tools, which nargo preserves but never reads
+    metadata: Option<toml::Value>,
+}
+
+/// A package metadata field which is either defined by the package itself or, when written as
+/// `field.workspace = true`, inherited from the workspace's `[workspace.package]` table.
+///
+/// As with Cargo, `field.workspace = false` is rejected when parsing rather than being read as
+/// a request not to inherit.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum MaybeWorkspace<T> {
+    Defined(T),
+    Workspace { workspace: bool },
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for MaybeWorkspace<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawMaybeWorkspace<T> {
+            Defined(T),
+            Workspace { workspace: bool },
+        }
+
+        match RawMaybeWorkspace::deserialize(deserializer)? {
+            RawMaybeWorkspace::Defined(value) => Ok(MaybeWorkspace::Defined(value)),
+            RawMaybeWorkspace::Workspace { workspace: true } => {
+                Ok(MaybeWorkspace::Workspace { workspace: true })
+            }
+            RawMaybeWorkspace::Workspace { workspace: false } => {
+                Err(serde::de::Error::custom("`workspace` cannot be false"))
+            }
+        }
+    }
+}
+
+impl PackageMetadata {
+    /// Returns the `[package.metadata]` table, kept verbatim for use by external tools.
+    pub fn metadata(&self) -> Option<&toml::Value> {
+        self.metadata.as_ref()
+    }
+
+    /// Returns the single link tooling should surface for the package's documentation: its
+    /// `documentation` if set, falling back to its `homepage` and then its `repository`.
+    pub fn effective_documentation(&self) -> Option<&str> {
+        self.documentation.as_deref().or(self.homepage.as_deref()).or(self.repository.as_deref())
+    }
+
+    /// Checks that `current`, the version of nargo in use, satisfies the package's `min_nargo`.
+    ///
+    /// Packages which do not specify a `min_nargo` are accepted by every version.
+    pub fn check_nargo_version(
+        &self,
+        current: &semver::Version,
+    ) -> Result<(), InvalidPackageError> {
+        let min_nargo = match &self.min_nargo {
+            Some(min_nargo) => min_nargo,
+            None => return Ok(()),
+        };
+
+        let required = semver::Version::parse(min_nargo)
+            .map_err(|_| InvalidPackageError::MalformedNargoVersion(min_nargo.clone()))?;
+
+        if current < &required {
+            Err(InvalidPackageError::NargoTooOld { required, current: current.clone() })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks that the package lists at most `MAX_KEYWORDS` keywords, each made up of lowercase
+    /// alphanumeric characters and dashes, as a package registry would require.
+    pub fn validate_keywords(&self) -> Result<(), InvalidPackageError> {
+        if self.keywords.len() > MAX_KEYWORDS {
+            return Err(InvalidPackageError::TooManyKeywords(self.keywords.len()));
+        }
+        for keyword in &self.keywords {
+            let is_valid = !keyword.is_empty()
+                && keyword
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+            if !is_valid {
+                return Err(InvalidPackageError::InvalidKeyword(keyword.clone()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the edition the package opts into, if any, is known to this version of nargo.
+    pub fn validate_edition(&self) -> Result<(), InvalidPackageError> {
+        match self.edition.as_deref() {
+            Some(edition) if Edition::parse(edition).is_none() => {
+                Err(InvalidPackageError::UnknownEdition(edition.to_owned()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the language edition the package opts into, defaulting to the earliest edition
+    /// for packages which do not specify one.
+    pub fn edition(&self) -> Edition {
+        match self.edition.as_deref() {
+            None => Edition::default(),
+            Some(edition) => Edition::parse(edition)
+                .expect("ice: edition should be validated when the manifest is parsed"),
+        }
+    }
+}
+
+/// A version of the language's syntax and semantics which a package can opt into.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Edition {
+    #[default]
+    Edition2023,
+}
+
+impl Edition {
+    /// Parses an edition as it is written in a manifest, e.g. `"2023"`.
+    fn parse(edition: &str) -> Option<Edition> {
+        match edition {
+            "2023" => Some(Edition::Edition2023),
+            _ => None,
+        }
+    }
+}
+
+/// Replaces `field` with the workspace's `shared` value if `field` is unset or requests
+/// inheritance.
+fn inherit_field<T>(
+    name: &'static str,
+    field: &mut Option<MaybeWorkspace<T>>,
+    shared: Option<MaybeWorkspace<T>>,
+) -> Result<(), InvalidPackageError> {
+    let requests_inheritance = matches!(field, Some(MaybeWorkspace::Workspace { workspace: true }));
+    if field.is_some() && !requests_inheritance {
+        return Ok(());
+    }
+
+    match shared {
+        Some(MaybeWorkspace::Defined(value)) => *field = Some(MaybeWorkspace::Defined(value)),
+        _ if requests_inheritance => return Err(InvalidPackageError::MissingWorkspaceField(name)),
+        _ => (),
+    }
+    Ok(())
+}
+
+/// The `[artifacts]` section of a manifest, overriding where build artifacts are written.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct ArtifactPaths {
+    pub proving_key: Option<PathBuf>,
+    pub verification_key: Option<PathBuf>,
+    pub acir: Option<PathBuf>,
+}
+
+impl ArtifactPaths {
+    /// Returns true if no artifact has its path overridden.
+    pub fn is_empty(&self) -> bool {
+        self.proving_key.is_none() && self.verification_key.is_none() && self.acir.is_none()
+    }
+}
+
+/// The inline threshold used when a profile does not set one. Every function is inlined,
+/// which matches the evaluator's behaviour when it is not given a threshold.
+pub const DEFAULT_INLINE_THRESHOLD: u32 = u32::MAX;
+
+/// A `[profile.<name>]` section of a manifest, configuring how the package is compiled.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Profile {
+    /// Functions larger than this many instructions are not inlined into their callers.
+    /// Defaults to `DEFAULT_INLINE_THRESHOLD`.
+    #[serde(default = "default_inline_threshold")]
+    pub inline_threshold: u32,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self { inline_threshold: DEFAULT_INLINE_THRESHOLD }
+    }
+}
+
+fn default_inline_threshold() -> u32 {
+    DEFAULT_INLINE_THRESHOLD
+}
+
+/// The `[public-inputs]` section of a manifest.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct PublicInputs {
+    /// Names of the public inputs in the order they should be laid out. When empty, the order
+    /// they are declared in is kept.
+    #[serde(default)]
+    pub order: Vec<String>,
+}
+
+impl PublicInputs {
+    /// Returns true if no public input order is declared.
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+}
+
+/// The `[workspace]` section of a manifest, listing the packages developed alongside it.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct WorkspaceConfig {
+    /// Paths to member packages relative to the manifest. These may be glob patterns such as
+    /// `crates/*`, which are expanded against the filesystem when the workspace is loaded.
+    pub members: Vec<String>,
+    /// Metadata shared by the members, from the `[workspace.package]` table.
+    pub package: Option<PackageMetadata>,
+    /// Whether members may live outside the directory containing the workspace manifest.
+    #[serde(default)]
+    pub allow_external_members: bool,
+    /// The level of each lint enforced across the members, from the `[workspace.lints]` table.
+    #[serde(default)]
+    pub lints: BTreeMap<String, String>,
+    /// Arbitrary configuration for external tools from the `[workspace.metadata]` table, which
+    /// nargo preserves but never reads.
+    pub metadata: Option<toml::Value>,
+}
+
+impl WorkspaceConfig {
+    /// Checks that the workspace could contain at least one member.
+    ///
+    /// A glob pattern is accepted even though it may turn out to match nothing, as that can
+    /// only be known once it is expanded.
+    pub fn validate(&self) -> Result<(), InvalidPackageError> {
+        if self.members.is_empty() {
+            return Err(InvalidPackageError::EmptyWorkspace);
+        }
+        for (lint, level) in &self.lints {
+            if LintLevel::parse(level).is_none() {
+                return Err(InvalidPackageError::UnknownLintLevel {
+                    lint: lint.clone(),
+                    level: level.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the level the workspace sets for the lint called `name`, defaulting to
+    /// `LintLevel::Allow` for lints it does not configure.
+    pub fn lint_level(&self, name: &str) -> LintLevel {
+        self.lints.get(name).and_then(|level| LintLevel::parse(level)).unwrap_or_default()
+    }
+
+    /// Resolves each member against `root`, the directory containing the workspace manifest,
+    /// returning their canonical paths.
+    ///
+    /// Glob patterns are not expanded: the directory preceding the first wildcard is resolved
+    /// instead and the remainder of the pattern is joined back onto it. Unless
+    /// `allow_external_members` is set, a member which does not live under `root` is rejected.
+    pub fn resolved_members(&self, root: &Path) -> Result<Vec<PathBuf>, InvalidPackageError> {
+        let root = canonicalize(root)?;
+        self.members
+            .iter()
+            .map(|member| {
+                let (directory, pattern) = split_glob(member);
+                let directory = canonicalize(&root.join(directory))?;
+                if !self.allow_external_members && !directory.starts_with(&root) {
+                    return Err(InvalidPackageError::MemberOutsideWorkspace(member.clone()));
+                }
+                Ok(match pattern {
+                    Some(pattern) => directory.join(pattern),
+                    None => directory,
+                })
+            })
+            .collect()
+    }
+}
+
+/// How a lint is enforced, as configured in `[workspace.lints]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintLevel {
+    /// The lint is not checked.
+    #[default]
+    Allow,
+    /// Violations of the lint are reported as warnings.
+    Warn,
+    /// Violations of the lint are reported as errors.
+    Deny,
+}
+
+impl LintLevel {
+    fn parse(level: &str) -> Option<LintLevel> {
+        match level {
+            "allow" => Some(LintLevel::Allow),
+            "warn" => Some(LintLevel::Warn),
+            "deny" => Some(LintLevel::Deny),
+            _ => None,
+        }
+    }
+}
+
+fn canonicalize(path: &Path) -> Result<PathBuf, InvalidPackageError> {
+    path.canonicalize().map_err(|_| InvalidPackageError::MissingManifestFile(path.to_path_buf()))
+}
+
+/// Splits a workspace member into the directory preceding its first wildcard component and
+/// the glob pattern which follows it, if any.
+fn split_glob(member: &str) -> (PathBuf, Option<PathBuf>) {
+    let components: Vec<_> = Path::new(member).components().collect();
+    let wildcard = components.iter().position(|component| {
+        component.as_os_str().to_string_lossy().contains(|c| matches!(c, '*' | '?' | '['))
+    });
+    match wildcard {
+        Some(index) => {
+            (components[..index].iter().collect(), Some(components[index..].iter().collect()))
+        }
+        None => (PathBuf::from(member), None),
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(untagged)]
 /// Enum representing the different types of ways to
 /// supply a source for the dependency
 pub enum Dependency {
-    Github { git: String, tag: String },
-    Path { path: String },
+    Github {
+        git: String,
+        tag: String,
+        /// Alternative URLs to fetch the dependency from if `git` cannot be reached.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        mirrors: Vec<String>,
+        /// Features to activate in the dependency.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        features: Vec<String>,
+        /// Whether the dependency is only active when enabled by a feature.
+        #[serde(default, skip_serializing_if = "is_false")]
+        optional: bool,
+        /// Whether the dependency's default features are activated, from `default-features`.
+        #[serde(
+            rename = "default-features",
+            default = "default_features",
+            skip_serializing_if = "is_true"
+        )]
+        default_features: bool,
+        /// The name of the package the dependency refers to, when it is imported under a
+        /// different name than its key.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        package: Option<String>,
+    },
+    Path {
+        path: String,
+        /// Features to activate in the dependency.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        features: Vec<String>,
+        /// Whether the dependency is only active when enabled by a feature.
+        #[serde(default, skip_serializing_if = "is_false")]
+        optional: bool,
+        /// Whether the dependency's default features are activated, from `default-features`.
+        #[serde(
+            rename = "default-features",
+            default = "default_features",
+            skip_serializing_if = "is_true"
+        )]
+        default_features: bool,
+        /// The name of the package the dependency refers to, when it is imported under a
+        /// different name than its key.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        package: Option<String>,
+    },
+}
+
+fn default_features() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+/// Returns true if `git` is an http(s) or ssh URL, or an scp-like ssh address such as
+/// `git@github.com:noir-lang/noir.git`.
+fn is_git_url(git: &str) -> bool {
+    if let Ok(url) = url::Url::parse(git) {
+        return matches!(url.scheme(), "http" | "https" | "ssh" | "git+ssh") && url.has_host();
+    }
+
+    match git.split_once(':') {
+        Some((user_host, path)) => match user_host.split_once('@') {
+            Some((user, host)) => {
+                !user.is_empty() && !host.is_empty() && !host.contains('/') && !path.is_empty()
+            }
+            None => false,
+        },
+        None => false,
+    }
+}
+
+/// Normalizes the spelling of a dependency path as described by `Dependency::normalized`.
+fn normalize_dependency_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let is_absolute = path.starts_with('/');
+    let components: Vec<_> =
+        path.split('/').filter(|component| !component.is_empty() && *component != ".").collect();
+
+    let normalized = components.join("/");
+    match (is_absolute, normalized.is_empty()) {
+        (true, _) => format!("/{normalized}"),
+        (false, true) => ".".to_string(),
+        (false, false) => normalized,
+    }
+}
+
+/// Spells `path`, written in the manifest of the package in directory `base`, relative to the
+/// directory `base` is itself relative to. `..` components are collapsed without consulting the
+/// filesystem, so `libs/b` and `../c` become `libs/c`.
+fn rebase_dependency_path(base: &str, path: &str) -> String {
+    let joined = if path.starts_with('/') { path.to_string() } else { format!("{base}/{path}") };
+    let normalized = normalize_dependency_path(&joined);
+    let is_absolute = normalized.starts_with('/');
+
+    let mut components: Vec<&str> = Vec::new();
+    for component in normalized.split('/').filter(|component| !component.is_empty()) {
+        match components.last() {
+            Some(last) if component == ".." && *last != ".." => {
+                components.pop();
+            }
+            _ => components.push(component),
+        }
+    }
+
+    let rebased = components.join("/");
+    match (is_absolute, rebased.is_empty()) {
+        (true, _) => format!("/{rebased}"),
+        (false, true) => ".".to_string(),
+        (false, false) => rebased,
+    }
+}
+
+impl Dependency {
+    /// Returns the features the dependent package requests be activated in this dependency.
+    pub fn requested_features(&self) -> &[String] {
+        match self {
+            Dependency::Github { features, .. } | Dependency::Path { features, .. } => features,
+        }
+    }
+
+    /// Returns the features which are enabled in this dependency: those requested by the
+    /// dependent package, along with `DEFAULT_FEATURE` unless it has been opted out of.
+    pub fn enabled_features(&self) -> Vec<String> {
+        let mut features = self.requested_features().to_vec();
+        if self.uses_default_features() {
+            features.push(DEFAULT_FEATURE.to_string());
+        }
+        features
+    }
+
+    /// Returns true if this dependency is only active when enabled by a feature.
+    pub fn is_optional(&self) -> bool {
+        match self {
+            Dependency::Github { optional, .. } | Dependency::Path { optional, .. } => *optional,
+        }
+    }
+
+    /// Returns true if the dependency's default features should be activated alongside the
+    /// requested features. This is only false when opted out with `default-features = false`.
+    pub fn uses_default_features(&self) -> bool {
+        match self {
+            Dependency::Github { default_features, .. }
+            | Dependency::Path { default_features, .. } => *default_features,
+        }
+    }
+
+    /// Returns the name of the package this dependency refers to: its `package` if it is
+    /// renamed, or otherwise `alias`, the name it is declared under.
+    pub fn real_name<'a>(&'a self, alias: &'a str) -> &'a str {
+        match self {
+            Dependency::Github { package, .. } | Dependency::Path { package, .. } => {
+                package.as_deref().unwrap_or(alias)
+            }
+        }
+    }
+
+    /// Checks that a git dependency's `git` field is an http(s) or ssh URL, so that a typo is
+    /// reported when the manifest is read rather than when the dependency is fetched.
+    pub fn validate(&self) -> Result<(), InvalidPackageError> {
+        match self {
+            Dependency::Github { git, .. } if !is_git_url(git) => {
+                Err(InvalidPackageError::MalformedGitUrl(git.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns a copy of this dependency with its path, if it is a path dependency, spelled
+    /// canonically: `/` as the only separator, with no `.` components, repeated separators,
+    /// or trailing separator. The filesystem is not consulted, so `..` components are kept.
+    pub fn normalized(&self) -> Dependency {
+        let mut dependency = self.clone();
+        if let Dependency::Path { path, .. } = &mut dependency {
+            *path = normalize_dependency_path(path);
+        }
+        dependency
+    }
+
+    /// Returns true if both dependencies refer to the same git URL and tag, or the same path.
+    fn has_same_source(&self, other: &Dependency) -> bool {
+        match (self, other) {
+            (
+                Dependency::Github { git, tag, .. },
+                Dependency::Github { git: other_git, tag: other_tag, .. },
+            ) => git == other_git && tag == other_tag,
+            (Dependency::Path { path, .. }, Dependency::Path { path: other_path, .. }) => {
+                path == other_path
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the URLs this dependency may be fetched from, in the order they should be tried:
+    /// the primary `git` URL followed by any mirrors.
+    ///
+    /// Path dependencies are never fetched so have no candidate URLs.
+    pub fn candidate_urls(&self) -> Vec<&str> {
+        match self {
+            Dependency::Github { git, mirrors, .. } => {
+                std::iter::once(git).chain(mirrors).map(String::as_str).collect()
+            }
+            Dependency::Path { .. } => Vec::new(),
+        }
+    }
 }
 
 #[test]
@@ -64,3 +905,1157 @@ fn parse_standard_toml() {
 
     assert!(PackageManifest::from_toml_str(src).is_ok());
 }
+
+#[test]
+fn malformed_toml_reports_line() {
+    let src = r#"[package]
+authors = ["kev"
+compiler_version = "0.1"
+
+[dependencies]
+"#;
+
+    let error = PackageManifest::from_toml_str(src).unwrap_err();
+    let error = match error {
+        InvalidPackageError::MalformedManifestFile(error) => error,
+        other => panic!("Expected a malformed manifest error, got {other:?}"),
+    };
+    let location = error.location.as_ref().expect("Expected error to have a location");
+    assert_eq!(location.line, 3);
+    assert_eq!(location.snippet, r#"compiler_version = "0.1""#);
+    assert!(error.to_string().contains("--> Nargo.toml:3:"));
+}
+
+#[test]
+fn min_nargo_version() {
+    let src = r#"
+        [package]
+        authors = []
+        min_nargo = "0.8.0"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    let satisfied = semver::Version::new(0, 8, 0);
+    assert!(manifest.package.check_nargo_version(&satisfied).is_ok());
+
+    let too_old = semver::Version::new(0, 7, 1);
+    assert!(matches!(
+        manifest.package.check_nargo_version(&too_old),
+        Err(InvalidPackageError::NargoTooOld { .. })
+    ));
+}
+
+#[test]
+fn edition() {
+    let src = r#"
+        [package]
+        authors = []
+        edition = "2023"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert_eq!(manifest.package.edition(), Edition::Edition2023);
+
+    assert_eq!(PackageMetadata::default().edition(), Edition::Edition2023);
+}
+
+#[test]
+fn unknown_edition() {
+    let src = r#"
+        [package]
+        authors = []
+        edition = "2015"
+
+        [dependencies]
+    "#;
+
+    assert!(matches!(
+        PackageManifest::from_toml_str(src),
+        Err(InvalidPackageError::UnknownEdition(edition)) if edition == "2015"
+    ));
+}
+
+#[test]
+fn keywords_and_categories() {
+    let src = r#"
+        [package]
+        authors = []
+        keywords = ["cryptography", "hash-functions", "sha256"]
+        categories = ["cryptography"]
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert_eq!(manifest.package.keywords, vec!["cryptography", "hash-functions", "sha256"]);
+    assert_eq!(manifest.package.categories, vec!["cryptography"]);
+}
+
+#[test]
+fn invalid_keyword() {
+    let src = r#"
+        [package]
+        authors = []
+        keywords = ["hashing", "Hash Functions"]
+
+        [dependencies]
+    "#;
+
+    assert!(matches!(
+        PackageManifest::from_toml_str(src),
+        Err(InvalidPackageError::InvalidKeyword(keyword)) if keyword == "Hash Functions"
+    ));
+}
+
+#[test]
+fn too_many_keywords() {
+    let src = r#"
+        [package]
+        authors = []
+        keywords = ["a", "b", "c", "d", "e", "f"]
+
+        [dependencies]
+    "#;
+
+    assert!(matches!(
+        PackageManifest::from_toml_str(src),
+        Err(InvalidPackageError::TooManyKeywords(6))
+    ));
+}
+
+#[test]
+fn dev_dependencies() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        hello = { path = "./hello" }
+
+        [dev-dependencies]
+        test_utils = { path = "./test_utils/" }
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert_eq!(manifest.dependencies.len(), 1);
+    assert!(matches!(
+        &manifest.dev_dependencies["test_utils"],
+        Dependency::Path { path, .. } if path == "test_utils"
+    ));
+}
+
+#[test]
+fn git_dependency_mirrors() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        rand = { tag = "next", git = "https://github.com/noir-lang/rand", mirrors = ["https://mirror-a.example/rand", "https://mirror-b.example/rand"] }
+        hello = { path = "./hello" }
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert_eq!(
+        manifest.dependencies["rand"].candidate_urls(),
+        vec![
+            "https://github.com/noir-lang/rand",
+            "https://mirror-a.example/rand",
+            "https://mirror-b.example/rand"
+        ]
+    );
+    assert!(manifest.dependencies["hello"].candidate_urls().is_empty());
+}
+
+#[test]
+fn empty_workspace_members() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [workspace]
+        members = []
+    "#;
+
+    assert!(matches!(
+        PackageManifest::from_toml_str(src),
+        Err(InvalidPackageError::EmptyWorkspace)
+    ));
+}
+
+#[test]
+fn glob_only_workspace_members() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [workspace]
+        members = ["crates/*"]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert_eq!(manifest.workspace.unwrap().members, vec!["crates/*"]);
+}
+
+#[test]
+fn workspace_lints() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [workspace]
+        members = ["crates/*"]
+
+        [workspace.lints]
+        path-dependencies = "deny"
+        missing-readme = "warn"
+    "#;
+    let workspace = PackageManifest::from_toml_str(src).unwrap().workspace.unwrap();
+
+    assert_eq!(workspace.lint_level("path-dependencies"), LintLevel::Deny);
+    assert_eq!(workspace.lint_level("missing-readme"), LintLevel::Warn);
+    assert_eq!(workspace.lint_level("unconfigured"), LintLevel::Allow);
+}
+
+#[test]
+fn unknown_lint_level() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [workspace]
+        members = ["crates/*"]
+
+        [workspace.lints]
+        path-dependencies = "forbid"
+    "#;
+
+    assert!(matches!(
+        PackageManifest::from_toml_str(src),
+        Err(InvalidPackageError::UnknownLintLevel { lint, level })
+            if lint == "path-dependencies" && level == "forbid"
+    ));
+}
+
+#[test]
+fn build_script() {
+    let src = r#"
+        [package]
+        authors = []
+        build = "gen.rs"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    assert_eq!(manifest.build_script(root), Some(root.join("gen.rs")));
+    assert!(matches!(
+        manifest.check_build_script(root),
+        Err(InvalidPackageError::MissingBuildScript(path)) if path == root.join("gen.rs")
+    ));
+
+    let src = r#"
+        [package]
+        authors = []
+        build = "src/lib.rs"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert!(manifest.check_build_script(root).is_ok());
+
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert_eq!(manifest.build_script(root), None);
+    assert!(manifest.check_build_script(root).is_ok());
+}
+
+#[test]
+fn artifact_paths() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [artifacts]
+        proving_key = "keys/main.pk"
+        acir = "/tmp/main.acir"
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert_eq!(manifest.artifact_paths.proving_key, Some(PathBuf::from("keys/main.pk")));
+    assert_eq!(manifest.artifact_paths.verification_key, None);
+
+    let root = Path::new("/packages/hello");
+    let artifacts = manifest.artifacts(root);
+    assert_eq!(artifacts.proving_key, Some(root.join("keys/main.pk")));
+    assert_eq!(artifacts.verification_key, None);
+    // Absolute paths are kept as they are
+    assert_eq!(artifacts.acir, Some(PathBuf::from("/tmp/main.acir")));
+}
+
+#[test]
+fn artifact_paths_default() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert_eq!(manifest.artifact_paths, ArtifactPaths::default());
+    assert_eq!(manifest.artifacts(Path::new("/packages/hello")), ArtifactPaths::default());
+}
+
+#[test]
+fn inherit_workspace_metadata() {
+    let workspace_src = r#"
+        [package]
+        authors = ["kev"]
+
+        [dependencies]
+
+        [workspace]
+        members = ["crates/*"]
+
+        [workspace.package]
+        authors = ["kev", "foo"]
+        license = "MIT"
+    "#;
+    let workspace = PackageManifest::from_toml_str(workspace_src).unwrap().workspace.unwrap();
+
+    let member_src = r#"
+        [package]
+        license.workspace = true
+
+        [dependencies]
+    "#;
+    let mut member = PackageManifest::from_toml_str(member_src).unwrap();
+    member.inherit_metadata(&workspace).unwrap();
+
+    let authors = vec!["kev".to_string(), "foo".to_string()];
+    assert_eq!(member.package.authors, Some(MaybeWorkspace::Defined(authors)));
+    assert_eq!(member.package.license, Some(MaybeWorkspace::Defined("MIT".to_string())));
+    assert_eq!(member.package.backend, None);
+
+    // Fields defined by the member itself take precedence
+    let member_src = r#"
+        [package]
+        authors = ["bar"]
+        license = "Apache-2.0"
+
+        [dependencies]
+    "#;
+    let mut member = PackageManifest::from_toml_str(member_src).unwrap();
+    member.inherit_metadata(&workspace).unwrap();
+
+    assert_eq!(member.package.authors, Some(MaybeWorkspace::Defined(vec!["bar".to_string()])));
+    assert_eq!(member.package.license, Some(MaybeWorkspace::Defined("Apache-2.0".to_string())));
+}
+
+#[test]
+fn inherit_undefined_workspace_field() {
+    let workspace_src = r#"
+        [package]
+
+        [dependencies]
+
+        [workspace]
+        members = ["a"]
+
+        [workspace.package]
+        license = "MIT"
+    "#;
+    let workspace = PackageManifest::from_toml_str(workspace_src).unwrap().workspace.unwrap();
+
+    let member_src = r#"
+        [package]
+        authors.workspace = true
+
+        [dependencies]
+    "#;
+    let mut member = PackageManifest::from_toml_str(member_src).unwrap();
+
+    assert!(matches!(
+        member.inherit_metadata(&workspace),
+        Err(InvalidPackageError::MissingWorkspaceField("authors"))
+    ));
+}
+
+#[test]
+fn reject_workspace_false() {
+    let src = r#"
+        [package]
+        license.workspace = false
+
+        [dependencies]
+    "#;
+
+    match PackageManifest::from_toml_str(src) {
+        Err(InvalidPackageError::MalformedManifestFile(error)) => {
+            assert!(error.message.contains("`workspace` cannot be false"), "{}", error.message);
+        }
+        other => panic!("expected a parse error, got {other:?}"),
+    }
+}
+
+#[test]
+fn implicit_std_dependency() {
+    let std_source = Dependency::Path {
+        path: "/noir/noir_stdlib".to_string(),
+        features: Vec::new(),
+        optional: false,
+        default_features: true,
+        package: None,
+    };
+
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        hello = { path = "./hello" }
+    "#;
+    let manifest =
+        PackageManifest::from_toml_str(src).unwrap().with_implicit_std(std_source.clone());
+    assert!(matches!(
+        &manifest.dependencies["std"],
+        Dependency::Path { path, .. } if path == "/noir/noir_stdlib"
+    ));
+    assert!(manifest.dependencies.contains_key("hello"));
+
+    // A user-pinned std is left alone
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        std = { tag = "v0.8.0", git = "https://github.com/noir-lang/noir" }
+    "#;
+    let manifest =
+        PackageManifest::from_toml_str(src).unwrap().with_implicit_std(std_source.clone());
+    assert!(
+        matches!(&manifest.dependencies["std"], Dependency::Github { tag, .. } if tag == "v0.8.0")
+    );
+
+    // Packages can opt out entirely
+    let src = r#"
+        [package]
+        authors = []
+        no_std = true
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap().with_implicit_std(std_source);
+    assert!(!manifest.dependencies.contains_key("std"));
+}
+
+#[test]
+fn dependency_features() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        rand = { tag = "v1", git = "https://github.com/noir-lang/rand", features = ["x", "y"] }
+        hello = { path = "./hello" }
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert_eq!(manifest.dependencies["rand"].requested_features(), ["x", "y"]);
+    assert!(manifest.dependencies["hello"].requested_features().is_empty());
+}
+
+#[test]
+fn dependency_default_features() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        rand = { tag = "v1", git = "https://github.com/noir-lang/rand", default-features = false, features = ["x"] }
+        hello = { path = "./hello" }
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    let rand = &manifest.dependencies["rand"];
+    assert!(!rand.uses_default_features());
+    assert_eq!(rand.requested_features(), ["x"]);
+    assert!(manifest.dependencies["hello"].uses_default_features());
+
+    assert_eq!(rand.enabled_features(), ["x"]);
+    assert_eq!(manifest.dependencies["hello"].enabled_features(), [DEFAULT_FEATURE]);
+}
+
+#[test]
+fn resolve_transitive_dependencies() {
+    // a -> (b, c, d), b -> c, d -> e, with e only enabled through d's `extra` feature. The
+    // optional `unused` is never enabled so is never fetched.
+    let manifests = [
+        (
+            ".",
+            r#"
+            [package]
+            authors = []
+
+            [dependencies]
+            b = { path = "libs/b" }
+            c = { path = "libs/c" }
+            d = { tag = "v1", git = "https://github.com/noir-lang/d", features = ["extra"] }
+            unused = { path = "libs/unused", optional = true }
+            "#,
+        ),
+        (
+            "libs/b",
+            r#"
+            [package]
+            authors = []
+
+            [dependencies]
+            c = { path = "../c" }
+            "#,
+        ),
+        (
+            "libs/c",
+            r#"
+            [package]
+            authors = []
+
+            [dependencies]
+            "#,
+        ),
+        (
+            "https://github.com/noir-lang/d",
+            r#"
+            [package]
+            authors = []
+
+            [dependencies]
+            e = { tag = "v1", git = "https://github.com/noir-lang/e", optional = true }
+
+            [features]
+            extra = ["e"]
+            "#,
+        ),
+        (
+            "https://github.com/noir-lang/e",
+            r#"
+            [package]
+            authors = []
+
+            [dependencies]
+            "#,
+        ),
+    ];
+    let fetch = |dependency: &Dependency| {
+        let key = match dependency {
+            Dependency::Github { git, .. } => git,
+            Dependency::Path { path, .. } => path,
+        };
+        let (_, src) = manifests
+            .iter()
+            .find(|(name, _)| *name == key.as_str())
+            .unwrap_or_else(|| panic!("unexpected fetch of {key}"));
+        PackageManifest::from_toml_str(src)
+    };
+
+    let root = PackageManifest::from_toml_str(manifests[0].1).unwrap();
+    let resolved = root.resolve_transitive(fetch).unwrap();
+
+    let names: Vec<_> = resolved.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, vec!["b", "c", "d", "e"]);
+    assert!(matches!(&resolved[0].1, Dependency::Path { path, .. } if path == "libs/b"));
+    assert!(matches!(&resolved[1].1, Dependency::Path { path, .. } if path == "libs/c"));
+}
+
+#[test]
+fn resolve_transitive_rebases_paths() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        b = { path = "libs/b" }
+        c = { path = "libs/c" }
+    "#;
+    // b's `c` is `libs/b/c` relative to the root, which is a different package to `libs/c`
+    let b_src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        c = { path = "c" }
+    "#;
+    let fetch = |dependency: &Dependency| match dependency {
+        Dependency::Path { path, .. } if path == "libs/b" => PackageManifest::from_toml_str(b_src),
+        _ => PackageManifest::from_toml_str("[package]\nauthors = []\n[dependencies]"),
+    };
+
+    let root = PackageManifest::from_toml_str(src).unwrap();
+    assert!(matches!(
+        root.resolve_transitive(fetch),
+        Err(InvalidPackageError::ConflictingDependencyVersions(name)) if name == "c"
+    ));
+
+    // A git dependency's path dependencies cannot be located
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        b = { tag = "v1", git = "https://github.com/noir-lang/b" }
+    "#;
+    let fetch = |_: &Dependency| PackageManifest::from_toml_str(b_src);
+
+    let root = PackageManifest::from_toml_str(src).unwrap();
+    assert!(matches!(
+        root.resolve_transitive(fetch),
+        Err(InvalidPackageError::RemoteDepWithLocalDep(name)) if name == "c"
+    ));
+}
+
+#[test]
+fn rebase_dependency_paths() {
+    assert_eq!(rebase_dependency_path(".", "libs/b"), "libs/b");
+    assert_eq!(rebase_dependency_path("libs/b", "../c"), "libs/c");
+    assert_eq!(rebase_dependency_path("libs/b", "./c/"), "libs/b/c");
+    assert_eq!(rebase_dependency_path("../b", "../../c"), "../../c");
+    assert_eq!(rebase_dependency_path("libs/b", "/noir/c"), "/noir/c");
+    assert_eq!(rebase_dependency_path("b", ".."), ".");
+}
+
+#[test]
+fn conflicting_dependency_versions() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        b = { tag = "v1", git = "https://github.com/noir-lang/b" }
+        c = { tag = "v1", git = "https://github.com/noir-lang/c" }
+    "#;
+    let b_src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        c = { tag = "v2", git = "https://github.com/noir-lang/c" }
+    "#;
+    let fetch = |dependency: &Dependency| match dependency {
+        Dependency::Github { git, .. } if git.ends_with("/b") => {
+            PackageManifest::from_toml_str(b_src)
+        }
+        _ => PackageManifest::from_toml_str("[package]\nauthors = []\n[dependencies]"),
+    };
+
+    let root = PackageManifest::from_toml_str(src).unwrap();
+    assert!(matches!(
+        root.resolve_transitive(fetch),
+        Err(InvalidPackageError::ConflictingDependencyVersions(name)) if name == "c"
+    ));
+}
+
+#[test]
+fn normalized_dependency_paths() {
+    let spellings =
+        ["lib/foo", "./lib/foo", "lib/foo/", "lib\\foo", ".\\lib\\foo\\", "./lib//./foo/"];
+    for spelling in spellings {
+        let dependency = Dependency::Path {
+            path: spelling.to_string(),
+            features: Vec::new(),
+            optional: false,
+            default_features: true,
+            package: None,
+        };
+        assert!(
+            matches!(dependency.normalized(), Dependency::Path { path, .. } if path == "lib/foo"),
+            "{spelling} was not normalized"
+        );
+    }
+
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        parent = { path = "../parent/" }
+        root = { path = "/noir//noir_stdlib/" }
+        here = { path = "./" }
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    let paths: Vec<_> = ["parent", "root", "here"]
+        .into_iter()
+        .map(|name| match &manifest.dependencies[name] {
+            Dependency::Path { path, .. } => path.as_str(),
+            Dependency::Github { .. } => unreachable!(),
+        })
+        .collect();
+    assert_eq!(paths, vec!["../parent", "/noir/noir_stdlib", "."]);
+}
+
+#[test]
+fn toml_round_trip() {
+    let src = r#"
+        [package]
+        authors = ["kev"]
+        compiler_version = "0.1"
+
+        [dependencies]
+        zeta = { tag = "v1", git = "https://github.com/noir-lang/zeta", features = ["x"] }
+        alpha = { path = "../alpha", default-features = false }
+        mid = { path = "../mid", optional = true }
+
+        [features]
+        extra = ["mid"]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    let reparsed = PackageManifest::from_toml_str(&manifest.to_toml_str()).unwrap();
+
+    let names = |manifest: &PackageManifest| -> Vec<String> {
+        manifest.dependencies.keys().cloned().collect()
+    };
+    assert_eq!(names(&manifest), vec!["zeta", "alpha", "mid"]);
+    assert_eq!(names(&reparsed), names(&manifest));
+
+    // Active dependencies keep the order they are written in too
+    let active = manifest.active_dependencies(&["extra".to_string()]);
+    let active: Vec<_> = active.into_iter().map(|(name, _)| name).collect();
+    assert_eq!(active, vec!["zeta", "alpha", "mid"]);
+
+    assert_eq!(reparsed.package.authors, manifest.package.authors);
+    assert_eq!(reparsed.dependencies["zeta"].requested_features(), ["x"]);
+    assert!(!reparsed.dependencies["alpha"].uses_default_features());
+    assert!(reparsed.dependencies["mid"].is_optional());
+    assert_eq!(reparsed.features, manifest.features);
+}
+
+#[test]
+fn renamed_dependency() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        rand_v1 = { tag = "v1", git = "https://github.com/noir-lang/rand", package = "rand" }
+        hello = { path = "./hello" }
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert_eq!(manifest.dependencies["rand_v1"].real_name("rand_v1"), "rand");
+    assert_eq!(manifest.dependencies["hello"].real_name("hello"), "hello");
+}
+
+#[test]
+fn publishing_metadata() {
+    let src = r#"
+        [package]
+        authors = []
+        description = "A library of random things"
+        readme = "README.md"
+        repository = "https://github.com/noir-lang/rand"
+        homepage = "https://noir-lang.org"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    let package = &manifest.package;
+    assert_eq!(package.description.as_deref(), Some("A library of random things"));
+    assert_eq!(package.readme, Some(PathBuf::from("README.md")));
+    assert_eq!(package.repository.as_deref(), Some("https://github.com/noir-lang/rand"));
+    assert_eq!(package.homepage.as_deref(), Some("https://noir-lang.org"));
+    assert!(manifest.validate_urls().is_ok());
+}
+
+#[test]
+fn malformed_urls() {
+    let src = r#"
+        [package]
+        authors = []
+        repository = "github.com/noir-lang/rand"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert!(matches!(
+        manifest.validate_urls(),
+        Err(InvalidPackageError::MalformedUrl { field: "repository", url })
+            if url == "github.com/noir-lang/rand"
+    ));
+}
+
+#[test]
+fn effective_documentation() {
+    let src = r#"
+        [package]
+        authors = []
+        repository = "https://github.com/noir-lang/rand"
+        homepage = "https://noir-lang.org"
+        documentation = "https://docs.noir-lang.org/rand"
+
+        [dependencies]
+    "#;
+    let mut manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert!(manifest.validate_urls().is_ok());
+
+    let package = &mut manifest.package;
+    assert_eq!(package.effective_documentation(), Some("https://docs.noir-lang.org/rand"));
+
+    package.documentation = None;
+    assert_eq!(package.effective_documentation(), Some("https://noir-lang.org"));
+
+    package.homepage = None;
+    assert_eq!(package.effective_documentation(), Some("https://github.com/noir-lang/rand"));
+
+    package.repository = None;
+    assert_eq!(package.effective_documentation(), None);
+}
+
+#[test]
+fn malformed_documentation_url() {
+    let src = r#"
+        [package]
+        authors = []
+        documentation = "docs.noir-lang.org"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert!(matches!(
+        manifest.validate_urls(),
+        Err(InvalidPackageError::MalformedUrl { field: "documentation", .. })
+    ));
+}
+
+#[test]
+fn profile_inline_threshold() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [profile.release]
+        inline_threshold = 20
+
+        [profile.debug]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    assert_eq!(manifest.profile("release").inline_threshold, 20);
+    assert_eq!(manifest.profile("debug").inline_threshold, DEFAULT_INLINE_THRESHOLD);
+    assert_eq!(manifest.profile("missing"), Profile::default());
+}
+
+#[test]
+fn optional_dependencies() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        hello = { path = "./hello" }
+        rand = { tag = "v1", git = "https://github.com/noir-lang/rand", optional = true }
+        unused = { path = "./unused", optional = true }
+
+        [features]
+        default = ["random"]
+        random = ["rand"]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    let names = |enabled_features: &[String]| -> Vec<&str> {
+        let active = manifest.active_dependencies(enabled_features);
+        active.into_iter().map(|(name, _)| name).collect()
+    };
+    assert_eq!(names(&[]), vec!["hello"]);
+
+    // `rand` is enabled indirectly through the `default` feature
+    assert_eq!(names(&["default".to_string()]), vec!["hello", "rand"]);
+
+    assert_eq!(manifest.unreachable_optional_dependencies(), vec!["unused"]);
+}
+
+#[test]
+fn member_outside_workspace() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/test_workspaces/member_outside_workspace/workspace");
+    let src = std::fs::read_to_string(root.join("Nargo.toml")).unwrap();
+    let mut workspace = PackageManifest::from_toml_str(&src).unwrap().workspace.unwrap();
+
+    assert!(matches!(
+        workspace.resolved_members(&root),
+        Err(InvalidPackageError::MemberOutsideWorkspace(member)) if member == "../outside"
+    ));
+
+    workspace.allow_external_members = true;
+    let members = workspace.resolved_members(&root).unwrap();
+    assert_eq!(members.len(), 2);
+    assert!(members[0].ends_with("workspace/inner"));
+    assert!(members[1].ends_with("member_outside_workspace/outside"));
+}
+
+#[test]
+fn minimal_manifest_round_trip() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    let rendered = manifest.to_toml_str();
+    assert!(!rendered.contains("artifacts"), "{rendered}");
+    assert!(!rendered.contains("public-inputs"), "{rendered}");
+
+    let reparsed = PackageManifest::from_toml_str(&rendered).unwrap();
+    assert!(reparsed.artifact_paths.is_empty());
+    assert!(reparsed.public_inputs.is_empty());
+
+    // Tables which declare something are still written out
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [artifacts]
+        acir = "build/main.acir"
+
+        [public-inputs]
+        order = ["y", "x"]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    let reparsed = PackageManifest::from_toml_str(&manifest.to_toml_str()).unwrap();
+    assert_eq!(reparsed.artifact_paths, manifest.artifact_paths);
+    assert_eq!(reparsed.public_inputs, manifest.public_inputs);
+}
+
+#[test]
+fn custom_metadata_round_trip() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [package.metadata.prover]
+        threads = 4
+        targets = ["wasm", "native"]
+
+        [package.metadata.prover.cache]
+        dir = "target/cache"
+
+        [dependencies]
+
+        [workspace]
+        members = ["crates/a"]
+
+        [workspace.metadata.ci]
+        nightly = true
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    let package_metadata = manifest.package.metadata().unwrap();
+    assert_eq!(package_metadata["prover"]["threads"].as_integer(), Some(4));
+    assert_eq!(package_metadata["prover"]["cache"]["dir"].as_str(), Some("target/cache"));
+
+    let reparsed = PackageManifest::from_toml_str(&manifest.to_toml_str()).unwrap();
+    assert_eq!(reparsed.package.metadata(), manifest.package.metadata());
+
+    let workspace_metadata = manifest.workspace.as_ref().unwrap().metadata.as_ref();
+    assert_eq!(workspace_metadata.unwrap()["ci"]["nightly"].as_bool(), Some(true));
+    assert_eq!(reparsed.workspace.unwrap().metadata.as_ref(), workspace_metadata);
+}
+
+#[test]
+fn valid_git_urls() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        https = { tag = "v1", git = "https://github.com/noir-lang/rand" }
+        ssh = { tag = "v1", git = "ssh://git@github.com/noir-lang/rand.git" }
+        scp = { tag = "v1", git = "git@github.com:noir-lang/rand.git" }
+    "#;
+    assert!(PackageManifest::from_toml_str(src).is_ok());
+}
+
+#[test]
+fn malformed_git_url() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+        rand = { tag = "v1", git = "htps//github.com/noir-lang/rand" }
+    "#;
+    assert!(matches!(
+        PackageManifest::from_toml_str(src),
+        Err(InvalidPackageError::MalformedGitUrl(git)) if git == "htps//github.com/noir-lang/rand"
+    ));
+}
+
+#[test]
+fn public_inputs_order() {
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+
+        [public-inputs]
+        order = ["y", "x"]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert_eq!(manifest.public_inputs.order, ["y", "x"]);
+
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert!(manifest.public_inputs.order.is_empty());
+}
+
+#[test]
+fn source_fingerprint() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("src/utils")).unwrap();
+    std::fs::write(root.join("src/main.nr"), "fn main() {}").unwrap();
+    std::fs::write(root.join("src/utils/mod.nr"), "fn helper() {}").unwrap();
+
+    let manifest =
+        PackageManifest::from_toml_str("[package]\nauthors = []\n[dependencies]").unwrap();
+    let fingerprint = manifest.source_fingerprint(root).unwrap();
+    assert_eq!(manifest.source_fingerprint(root).unwrap(), fingerprint);
+
+    std::fs::write(root.join("src/utils/mod.nr"), "fn helper() { }").unwrap();
+    let edited = manifest.source_fingerprint(root).unwrap();
+    assert_ne!(edited, fingerprint);
+    assert_eq!(manifest.source_fingerprint(root).unwrap(), edited);
+
+    let mut described = manifest.clone();
+    described.package.description = Some("A library".to_string());
+    assert_ne!(described.source_fingerprint(root).unwrap(), edited);
+}
+
+#[test]
+fn default_run() {
+    let src = r#"
+        [package]
+        authors = []
+        default-run = "src/lib.rs"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert_eq!(manifest.default_run(), Some("src/lib.rs"));
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    assert!(manifest.check_default_run(root).is_ok());
+
+    let src = r#"
+        [package]
+        authors = []
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    assert_eq!(manifest.default_run(), None);
+    assert!(manifest.check_default_run(root).is_ok());
+}
+
+#[test]
+fn missing_default_run() {
+    let src = r#"
+        [package]
+        authors = []
+        default-run = "src/transfer.nr"
+
+        [dependencies]
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    assert!(matches!(
+        manifest.check_default_run(root),
+        Err(InvalidPackageError::MissingDefaultRun(path)) if path == root.join("src/transfer.nr")
+    ));
+}
+
+#[test]
+fn json_schema() {
+    let schema = PackageManifest::json_schema();
+    let properties = &schema["properties"];
+    assert!(properties.get("package").is_some());
+    assert!(properties.get("dependencies").is_some());
+
+    let forms = schema["$defs"]["dependency"]["oneOf"].as_array().unwrap();
+    let required: Vec<_> = forms.iter().map(|form| form["required"][0].as_str().unwrap()).collect();
+    assert_eq!(required, ["git", "path"]);
+    for form in forms {
+        assert!(form["properties"].get("default-features").is_some());
+    }
+
+    // Only `workspace = true` is accepted when inheriting a field
+    let license = &schema["$defs"]["package"]["properties"]["license"]["oneOf"][1];
+    assert_eq!(license["properties"]["workspace"], serde_json::json!({ "const": true }));
+
+    // Every key a populated manifest serializes to must be described by the schema
+    let src = r#"
+        [package]
+        authors = ["kev"]
+        compiler_version = "0.1"
+        backend = "barretenberg"
+        license = "MIT"
+        min_nargo = "0.1.0"
+        build = "gen.rs"
+        default-run = "src/main.nr"
+        no_std = true
+        description = "A library"
+        readme = "README.md"
+        repository = "https://github.com/noir-lang/rand"
+        homepage = "https://noir-lang.org"
+        documentation = "https://noir-lang.org/docs"
+        edition = "2023"
+        keywords = ["random"]
+        categories = ["cryptography"]
+        metadata = { tool = true }
+
+        [dependencies]
+        rand = { tag = "v1", git = "https://github.com/noir-lang/rand" }
+    "#;
+    let manifest = PackageManifest::from_toml_str(src).unwrap();
+    let serialized = toml::Value::try_from(&manifest).unwrap();
+    for key in serialized.as_table().unwrap().keys() {
+        assert!(properties.get(key).is_some(), "schema is missing `{key}`");
+    }
+    for key in serialized["package"].as_table().unwrap().keys() {
+        let package_properties = &schema["$defs"]["package"]["properties"];
+        assert!(package_properties.get(key).is_some(), "schema is missing `package.{key}`");
+    }
+}
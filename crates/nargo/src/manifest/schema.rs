@@ -0,0 +1,156 @@
+//! A JSON Schema describing `Nargo.toml`, for editors to offer completion and validation.
+//!
+//! The schema is maintained by hand alongside the manifest types in the parent module, so any
+//! field added to them must also be added here.
+use serde_json::{json, Value};
+
+use super::{PackageManifest, MAX_KEYWORDS};
+
+impl PackageManifest {
+    /// Returns a JSON Schema (draft 2020-12) describing every table and field recognized in a
+    /// package or workspace manifest.
+    pub fn json_schema() -> Value {
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Nargo.toml",
+            "type": "object",
+            "required": ["package", "dependencies"],
+            "properties": {
+                "package": { "$ref": "#/$defs/package" },
+                "dependencies": { "$ref": "#/$defs/dependencies" },
+                "dev-dependencies": { "$ref": "#/$defs/dependencies" },
+                "workspace": workspace_schema(),
+                "artifacts": {
+                    "type": "object",
+                    "properties": {
+                        "proving_key": { "type": "string" },
+                        "verification_key": { "type": "string" },
+                        "acir": { "type": "string" },
+                    },
+                },
+                "profile": {
+                    "type": "object",
+                    "additionalProperties": {
+                        "type": "object",
+                        "properties": {
+                            "inline_threshold": { "type": "integer", "minimum": 0 },
+                        },
+                    },
+                },
+                "features": {
+                    "type": "object",
+                    "additionalProperties": { "type": "array", "items": { "type": "string" } },
+                },
+                "public-inputs": {
+                    "type": "object",
+                    "properties": {
+                        "order": { "type": "array", "items": { "type": "string" } },
+                    },
+                },
+            },
+            "$defs": {
+                "package": package_schema(),
+                "dependencies": {
+                    "type": "object",
+                    "additionalProperties": { "$ref": "#/$defs/dependency" },
+                },
+                "dependency": dependency_schema(),
+            },
+        })
+    }
+}
+
+/// The `[package]` table, which is also used for `[workspace.package]`.
+fn package_schema() -> Value {
+    let inheritable = |schema: Value| {
+        json!({
+            "oneOf": [
+                schema,
+                {
+                    "type": "object",
+                    "required": ["workspace"],
+                    "properties": { "workspace": { "const": true } },
+                },
+            ],
+        })
+    };
+    let strings = json!({ "type": "array", "items": { "type": "string" } });
+    let string = json!({ "type": "string" });
+    let url = json!({ "type": "string", "format": "uri" });
+
+    json!({
+        "type": "object",
+        "properties": {
+            "authors": inheritable(strings.clone()),
+            "compiler_version": inheritable(string.clone()),
+            "backend": inheritable(string.clone()),
+            "license": inheritable(string.clone()),
+            "min_nargo": string,
+            "build": string,
+            "default-run": string,
+            "no_std": { "type": "boolean" },
+            "description": string,
+            "readme": string,
+            "repository": url,
+            "homepage": url,
+            "documentation": url,
+            "edition": { "enum": ["2023"] },
+            "keywords": {
+                "type": "array",
+                "maxItems": MAX_KEYWORDS,
+                "items": { "type": "string", "pattern": "^[a-z0-9-]+$" },
+            },
+            "categories": strings,
+            "metadata": {},
+        },
+    })
+}
+
+/// A single dependency, in either its git or its path form.
+fn dependency_schema() -> Value {
+    // Fields accepted by both forms, added alongside each form's source fields
+    let shared = |mut properties: Value| {
+        properties["features"] = json!({ "type": "array", "items": { "type": "string" } });
+        properties["optional"] = json!({ "type": "boolean" });
+        properties["default-features"] = json!({ "type": "boolean" });
+        properties["package"] = json!({ "type": "string" });
+        properties
+    };
+
+    json!({
+        "oneOf": [
+            {
+                "type": "object",
+                "required": ["git", "tag"],
+                "properties": shared(json!({
+                    "git": { "type": "string" },
+                    "tag": { "type": "string" },
+                    "mirrors": { "type": "array", "items": { "type": "string" } },
+                })),
+            },
+            {
+                "type": "object",
+                "required": ["path"],
+                "properties": shared(json!({ "path": { "type": "string" } })),
+            },
+        ],
+    })
+}
+
+/// The `[workspace]` table.
+fn workspace_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["members"],
+        "properties": {
+            "members": { "type": "array", "items": { "type": "string" } },
+            "package": { "$ref": "#/$defs/package" },
+            "allow_external_members": { "type": "boolean" },
+            "lints": {
+                "type": "object",
+                "additionalProperties": { "enum": ["allow", "warn", "deny"] },
+            },
+            "metadata": {},
+        },
+    })
+}
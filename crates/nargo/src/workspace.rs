@@ -2,3 +2,234 @@
 // Then we use workspace to allow more than one. In the future, do not allow there to be
 // both a binary and a library.
 // - library will be default
+
+use std::{
+    collections::BTreeSet,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::manifest::{Dependency, InvalidPackageError, PackageManifest};
+
+/// A set of local packages which are developed together.
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    /// The directory of each member package, relative to the workspace root.
+    pub members: Vec<PathBuf>,
+}
+
+impl Workspace {
+    /// Builds the graph of path dependencies between the members of the workspace rooted at `root`.
+    ///
+    /// Local packages reached through path dependencies are included even if they are not
+    /// listed as members. Git dependencies are never included as they cannot be members.
+    pub fn dependency_graph(&self, root: &Path) -> Result<DepGraph, InvalidPackageError> {
+        let mut graph = DepGraph::default();
+        let mut stack: Vec<PathBuf> =
+            self.members.iter().map(|member| normalize_path(member)).collect();
+
+        while let Some(package_dir) = stack.pop() {
+            if !graph.nodes.insert(package_dir.clone()) {
+                continue;
+            }
+
+            let manifest = read_manifest(&root.join(&package_dir))?;
+            let dependencies = manifest
+                .dependencies
+                .values()
+                .map(|dependency| (dependency, false))
+                .chain(manifest.dev_dependencies.values().map(|dependency| (dependency, true)));
+            for (dependency, is_dev) in dependencies {
+                if let Dependency::Path { path, .. } = dependency {
+                    let dependency_dir = normalize_path(&package_dir.join(path));
+                    let edges = if is_dev { &mut graph.dev_edges } else { &mut graph.edges };
+                    edges.insert((package_dir.clone(), dependency_dir.clone()));
+                    stack.push(dependency_dir);
+                }
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Orders the packages of the workspace rooted at `root` so that each package comes after
+    /// every package it depends on.
+    ///
+    /// Normal dependencies must form no cycles, otherwise a `DependencyCycle` error lists the
+    /// packages which could not be ordered. Dev-dependencies are honoured where possible but
+    /// may form cycles, as tests are built after every package: when no remaining package has
+    /// all of its dependencies built, the first package by path whose normal dependencies are
+    /// built comes next.
+    pub fn build_order_with_dev(&self, root: &Path) -> Result<Vec<PathBuf>, InvalidPackageError> {
+        let graph = self.dependency_graph(root)?;
+        let mut remaining = graph.nodes.clone();
+        let mut order = Vec::new();
+
+        while !remaining.is_empty() {
+            let next = {
+                let is_ready = |edges: &BTreeSet<(PathBuf, PathBuf)>, package: &PathBuf| {
+                    !edges.iter().any(|(from, to)| from == package && remaining.contains(to))
+                };
+                remaining
+                    .iter()
+                    .find(|package| {
+                        is_ready(&graph.edges, package) && is_ready(&graph.dev_edges, package)
+                    })
+                    .or_else(|| remaining.iter().find(|package| is_ready(&graph.edges, package)))
+                    .cloned()
+            };
+
+            match next {
+                Some(package) => {
+                    remaining.remove(&package);
+                    order.push(package);
+                }
+                None => {
+                    return Err(InvalidPackageError::DependencyCycle(
+                        remaining.into_iter().collect(),
+                    ))
+                }
+            }
+        }
+
+        Ok(order)
+    }
+}
+
+/// The path dependencies between a set of local packages.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DepGraph {
+    /// The directory of each package, relative to the workspace root.
+    pub nodes: BTreeSet<PathBuf>,
+    /// Each edge is a `(dependent, dependency)` pair of package directories.
+    pub edges: BTreeSet<(PathBuf, PathBuf)>,
+    /// Edges from each package to its dev-dependencies, in the same form as `edges`.
+    pub dev_edges: BTreeSet<(PathBuf, PathBuf)>,
+}
+
+impl DepGraph {
+    /// Renders the graph in Graphviz DOT format, with an edge pointing from each package to
+    /// each of its dependencies.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph workspace {\n");
+        for node in &self.nodes {
+            let name = node.file_name().map_or_else(
+                || node.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            dot.push_str(&format!("  {} [label={}];\n", quoted(node), quoted(name)));
+        }
+        for (dependent, dependency) in &self.edges {
+            dot.push_str(&format!("  {} -> {};\n", quoted(dependent), quoted(dependency)));
+        }
+        for (dependent, dependency) in &self.dev_edges {
+            dot.push_str(&format!(
+                "  {} -> {} [style=dashed];\n",
+                quoted(dependent),
+                quoted(dependency)
+            ));
+        }
+        dot.push('}');
+        dot
+    }
+}
+
+/// Quotes `value` for use as a DOT identifier.
+fn quoted(value: impl AsRef<Path>) -> String {
+    let value = value.as_ref().display().to_string();
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Reads the manifest of the package in `package_dir`.
+fn read_manifest(package_dir: &Path) -> Result<PackageManifest, InvalidPackageError> {
+    let manifest_path = package_dir.join("Nargo.toml");
+    let toml_as_string = std::fs::read_to_string(manifest_path)
+        .map_err(|_| InvalidPackageError::MissingManifestFile(package_dir.to_path_buf()))?;
+    PackageManifest::from_toml_str(&toml_as_string)
+}
+
+/// Removes `.` components and resolves `..` components of `path` without touching the
+/// filesystem, so that different spellings of the same relative path compare equal.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                let ends_in_parent =
+                    matches!(normalized.components().last(), None | Some(Component::ParentDir));
+                if ends_in_parent {
+                    normalized.push("..");
+                } else {
+                    normalized.pop();
+                }
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::Workspace;
+    use crate::manifest::InvalidPackageError;
+
+    const TEST_DATA_DIR: &str = "tests/test_workspaces";
+
+    #[test]
+    fn dependency_graph() {
+        let root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join(TEST_DATA_DIR).join("dependency_graph");
+        let workspace = Workspace { members: vec![PathBuf::from("a"), PathBuf::from("./b")] };
+
+        let graph = workspace.dependency_graph(&root).unwrap();
+
+        // `c` is not a member but is reached through path dependencies, while the git dependency
+        // of `a` is left out.
+        let nodes: Vec<_> = graph.nodes.iter().map(|node| node.to_str().unwrap()).collect();
+        assert_eq!(nodes, vec!["a", "b", "c"]);
+
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|(from, to)| (from.to_str().unwrap(), to.to_str().unwrap()))
+            .collect();
+        assert_eq!(edges, vec![("a", "b"), ("a", "c"), ("b", "c")]);
+
+        let expected_dot = r#"digraph workspace {
+  "a" [label="a"];
+  "b" [label="b"];
+  "c" [label="c"];
+  "a" -> "b";
+  "a" -> "c";
+  "b" -> "c";
+}"#;
+        assert_eq!(graph.to_dot(), expected_dot);
+    }
+
+    #[test]
+    fn build_order_with_dev_cycle() {
+        let root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join(TEST_DATA_DIR).join("dev_dependency_cycle");
+        let workspace = Workspace { members: vec![PathBuf::from("a"), PathBuf::from("b")] };
+
+        // `a` depends on `b`, while `b` only dev-depends on `a`
+        let order = workspace.build_order_with_dev(&root).unwrap();
+        assert_eq!(order, vec![PathBuf::from("b"), PathBuf::from("a")]);
+    }
+
+    #[test]
+    fn build_order_with_dependency_cycle() {
+        let root =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join(TEST_DATA_DIR).join("dependency_cycle");
+        let workspace = Workspace { members: vec![PathBuf::from("a")] };
+
+        assert!(matches!(
+            workspace.build_order_with_dev(&root),
+            Err(InvalidPackageError::DependencyCycle(packages))
+                if packages == vec![PathBuf::from("a"), PathBuf::from("b")]
+        ));
+    }
+}
@@ -25,7 +25,7 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
         return Ok(loc);
     }
 
-    Command::new("git")
+    let status = Command::new("git")
         .arg("-c")
         .arg("advice.detachedHead=false")
         .arg("clone")
@@ -38,5 +38,9 @@ pub(crate) fn clone_git_repo(url: &str, tag: &str) -> Result<PathBuf, String> {
         .status()
         .expect("git clone command failed to start");
 
+    if !status.success() {
+        return Err(format!("failed to clone {base} at tag {tag}"));
+    }
+
     Ok(loc)
 }
@@ -11,5 +11,16 @@ pub(crate) fn parse<P: AsRef<Path>>(
     let toml_as_string =
         std::fs::read_to_string(&path_to_toml).expect("ice: path given for toml file is invalid");
 
-    PackageManifest::from_toml_str(&toml_as_string)
+    let manifest = PackageManifest::from_toml_str(&toml_as_string)?;
+
+    let nargo_version = semver::Version::parse(env!("CARGO_PKG_VERSION"))
+        .expect("ice: nargo's own version should be valid semver");
+    manifest.package.check_nargo_version(&nargo_version)?;
+
+    let package_root =
+        path_to_toml.as_ref().parent().expect("ice: toml file should have a parent directory");
+    manifest.check_build_script(package_root)?;
+    manifest.check_default_run(package_root)?;
+
+    Ok(manifest)
 }
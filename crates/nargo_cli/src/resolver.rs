@@ -3,8 +3,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use nargo::manifest::{Dependency, PackageManifest};
+use nargo::manifest::{Dependency, PackageManifest, DEFAULT_FEATURE};
 use noirc_driver::{add_dep, create_local_crate, create_non_local_crate};
+use noirc_errors::reporter::{self, CustomDiagnostic, DiagnosticKind};
 use noirc_frontend::{
     graph::{CrateId, CrateType},
     hir::Context,
@@ -72,29 +73,49 @@ pub(crate) fn resolve_root_manifest(
     let manifest = super::manifest::parse(&manifest_path)?;
 
     let crate_id = create_local_crate(&mut context, entry_path, crate_type);
+    report_unreachable_optional_dependencies(&context, &manifest);
 
     let pkg_root = manifest_path.parent().expect("Every manifest path has a parent.");
-    resolve_manifest(&mut context, crate_id, manifest, pkg_root)?;
+    let enabled_features = [DEFAULT_FEATURE.to_string()];
+    resolve_manifest(&mut context, crate_id, manifest, pkg_root, &enabled_features)?;
 
     Ok(context)
 }
 
+/// Warns about each optional dependency of `manifest` which no feature enables.
+///
+/// This is only done for the root package, as the unused optional dependencies of a dependency
+/// are not something the user can act upon.
+fn report_unreachable_optional_dependencies(context: &Context, manifest: &PackageManifest) {
+    for dependency in manifest.unreachable_optional_dependencies() {
+        let mut diagnostic = CustomDiagnostic::from_message(&format!(
+            "optional dependency `{dependency}` is not enabled by any feature, so it will never be used"
+        ));
+        diagnostic.kind = DiagnosticKind::Warning;
+        reporter::report(&context.file_manager, &diagnostic, None, false);
+    }
+}
+
 // Resolves a config file by recursively resolving the dependencies in the config
 // Need to solve the case of a project trying to use itself as a dep
 //
 // We do not need to add stdlib, as it's implicitly
 // imported. However, it may be helpful to have the stdlib imported by the
 // package manager.
+//
+// Only the dependencies which are active under `enabled_features` are resolved, so optional
+// dependencies which no enabled feature activates are never fetched.
 fn resolve_manifest(
     context: &mut Context,
     parent_crate: CrateId,
     manifest: PackageManifest,
     pkg_root: &Path,
+    enabled_features: &[String],
 ) -> Result<(), DependencyResolutionError> {
-    let mut cached_packages: HashMap<PathBuf, (CrateId, CachedDep)> = HashMap::new();
+    let mut cached_packages: HashMap<PathBuf, (CrateId, CachedDep, Vec<String>)> = HashMap::new();
 
     // First download and add these top level dependencies crates to the Driver
-    for (dep_pkg_name, pkg_src) in manifest.dependencies.iter() {
+    for (dep_pkg_name, pkg_src) in manifest.active_dependencies(enabled_features) {
         let (dir_path, dep_meta) = cache_dep(pkg_src, pkg_root)?;
 
         let (entry_path, crate_type) = (&dep_meta.entry_path, &dep_meta.crate_type);
@@ -108,16 +129,16 @@ fn resolve_manifest(
         let crate_id = create_non_local_crate(context, entry_path, *crate_type);
         add_dep(context, parent_crate, crate_id, dep_pkg_name);
 
-        cached_packages.insert(dir_path, (crate_id, dep_meta));
+        cached_packages.insert(dir_path, (crate_id, dep_meta, pkg_src.enabled_features()));
     }
 
     // Resolve all transitive dependencies
-    for (dependency_path, (crate_id, dep_meta)) in cached_packages {
+    for (dependency_path, (crate_id, dep_meta, dep_features)) in cached_packages {
         if dep_meta.remote && dep_meta.manifest.has_local_dependency() {
             return Err(DependencyResolutionError::RemoteDepWithLocalDep { dependency_path });
         }
         // TODO: Why did it create a new resolver?
-        resolve_manifest(context, crate_id, dep_meta.manifest, &dependency_path)?;
+        resolve_manifest(context, crate_id, dep_meta.manifest, &dependency_path, &dep_features)?;
     }
     Ok(())
 }
@@ -143,15 +164,69 @@ fn cache_dep(
     }
 
     match dep {
-        Dependency::Github { git, tag } => {
-            let dir_path = clone_git_repo(git, tag).map_err(DependencyResolutionError::GitError)?;
-            let meta = retrieve_meta(&dir_path, true)?;
-            Ok((dir_path, meta))
+        Dependency::Github { tag, .. } => {
+            // Try the primary URL first, falling back to each mirror in turn.
+            let mut errors = Vec::new();
+            for url in dep.candidate_urls() {
+                match clone_git_repo(url, tag) {
+                    Ok(dir_path) => {
+                        let meta = retrieve_meta(&dir_path, true)?;
+                        return Ok((dir_path, meta));
+                    }
+                    Err(error) => errors.push(error),
+                }
+            }
+            Err(DependencyResolutionError::GitError(errors.join("\n")))
         }
-        Dependency::Path { path } => {
+        Dependency::Path { path, .. } => {
             let dir_path = pkg_root.join(path);
             let meta = retrieve_meta(&dir_path, false)?;
             Ok((dir_path, meta))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempdir::TempDir;
+
+    use super::resolve_root_manifest;
+
+    /// Writes a binary package whose only dependency, `missing`, points at a directory which
+    /// does not exist, so resolution only succeeds if that dependency is skipped.
+    fn write_package(dir: &TempDir, features: &str) {
+        let manifest = format!(
+            r#"
+            [package]
+            authors = []
+
+            [dependencies]
+            missing = {{ path = "does_not_exist", optional = true }}
+
+            [features]
+            {features}
+            "#
+        );
+        fs::write(dir.path().join("Nargo.toml"), manifest).unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("src").join("main.nr"), "fn main() {}").unwrap();
+    }
+
+    #[test]
+    fn inactive_optional_dependency_is_not_resolved() {
+        let dir = TempDir::new("inactive_optional").unwrap();
+        write_package(&dir, "");
+
+        assert!(resolve_root_manifest(dir.path()).is_ok());
+    }
+
+    #[test]
+    fn default_feature_activates_optional_dependency() {
+        let dir = TempDir::new("active_optional").unwrap();
+        write_package(&dir, r#"default = ["missing"]"#);
+
+        assert!(resolve_root_manifest(dir.path()).is_err());
+    }
+}
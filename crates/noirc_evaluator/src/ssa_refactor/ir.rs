@@ -2,11 +2,16 @@ pub(crate) mod basic_block;
 pub(crate) mod cfg;
 pub(crate) mod dfg;
 pub(crate) mod dom;
+pub(crate) mod errors;
 pub(crate) mod function;
 pub(crate) mod function_inserter;
 pub(crate) mod instruction;
+pub(crate) mod liveness;
+pub(crate) mod loops;
 pub(crate) mod map;
 pub(crate) mod post_order;
 pub(crate) mod printer;
+pub(crate) mod region;
 pub(crate) mod types;
 pub(crate) mod value;
+pub(crate) mod verify;
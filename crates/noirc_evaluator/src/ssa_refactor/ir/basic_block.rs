@@ -1,4 +1,7 @@
+use std::collections::BTreeMap;
+
 use super::{
+    dfg::DataFlowGraph,
     instruction::{InstructionId, TerminatorInstruction},
     map::Id,
     value::ValueId,
@@ -23,6 +26,10 @@ pub(crate) struct BasicBlock {
     /// This will be a control flow instruction. This is only
     /// None if the block is still being constructed.
     terminator: Option<TerminatorInstruction>,
+
+    /// Analysis results attached to the block by passes, such as a loop depth or a "cold"
+    /// flag. These are ignored when generating code.
+    metadata: BTreeMap<String, String>,
 }
 
 /// An identifier for a Basic Block.
@@ -32,7 +39,12 @@ impl BasicBlock {
     /// Create a new BasicBlock with the given parameters.
     /// Parameters can also be added later via BasicBlock::add_parameter
     pub(crate) fn new() -> Self {
-        Self { parameters: Vec::new(), instructions: Vec::new(), terminator: None }
+        Self {
+            parameters: Vec::new(),
+            instructions: Vec::new(),
+            terminator: None,
+            metadata: BTreeMap::new(),
+        }
     }
 
     /// Returns the parameters of this block
@@ -115,6 +127,27 @@ impl BasicBlock {
         std::mem::replace(terminator, TerminatorInstruction::Return { return_values: Vec::new() })
     }
 
+    /// Returns true if this block ends in a conditional jump (JmpIf).
+    ///
+    /// This is false if the block has no terminator yet.
+    pub(crate) fn is_conditional(&self) -> bool {
+        matches!(self.terminator, Some(TerminatorInstruction::JmpIf { .. }))
+    }
+
+    /// Returns true if this block ends in an unconditional jump (Jmp).
+    ///
+    /// This is false if the block has no terminator yet.
+    pub(crate) fn is_unconditional_jump(&self) -> bool {
+        matches!(self.terminator, Some(TerminatorInstruction::Jmp { .. }))
+    }
+
+    /// Returns true if this block returns from the current function.
+    ///
+    /// This is false if the block has no terminator yet.
+    pub(crate) fn is_return(&self) -> bool {
+        matches!(self.terminator, Some(TerminatorInstruction::Return { .. }))
+    }
+
     /// Return the jmp arguments, if any, of this block's TerminatorInstruction.
     ///
     /// If this block has no terminator, or a Return terminator this will be empty.
@@ -141,6 +174,29 @@ impl BasicBlock {
         }
     }
 
+    /// Attaches `value` to this block under `key`, replacing any previous value.
+    pub(crate) fn set_meta(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.metadata.insert(key.into(), value.into());
+    }
+
+    /// Returns the value attached to this block under `key`, if any.
+    pub(crate) fn get_meta(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Returns all of the metadata attached to this block.
+    pub(crate) fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Returns the values this block introduces: its parameters followed by the results of
+    /// each of its instructions, in order.
+    pub(crate) fn defined_values(&self, dfg: &DataFlowGraph) -> Vec<ValueId> {
+        let results =
+            self.instructions.iter().flat_map(|instruction| dfg.instruction_results(*instruction));
+        self.parameters.iter().chain(results).copied().collect()
+    }
+
     /// Removes the given instruction from this block if present or panics otherwise.
     pub(crate) fn remove_instruction(&mut self, instruction: InstructionId) {
         let index =
@@ -150,3 +206,91 @@ impl BasicBlock {
         self.instructions.remove(index);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{BasicBlock, TerminatorInstruction};
+    use crate::ssa_refactor::ir::{
+        dfg::DataFlowGraph, instruction::Instruction, map::Id, types::Type,
+    };
+
+    #[test]
+    fn terminator_predicates() {
+        let mut block = BasicBlock::new();
+        assert!(!block.is_conditional());
+        assert!(!block.is_unconditional_jump());
+        assert!(!block.is_return());
+
+        let condition = Id::test_new(0);
+        block.set_terminator(TerminatorInstruction::JmpIf {
+            condition,
+            then_destination: Id::test_new(1),
+            else_destination: Id::test_new(2),
+        });
+        assert!(block.is_conditional());
+        assert!(!block.is_unconditional_jump());
+        assert!(!block.is_return());
+
+        block.set_terminator(TerminatorInstruction::Jmp {
+            destination: Id::test_new(1),
+            arguments: vec![condition],
+        });
+        assert!(!block.is_conditional());
+        assert!(block.is_unconditional_jump());
+        assert!(!block.is_return());
+
+        block.set_terminator(TerminatorInstruction::Return { return_values: vec![condition] });
+        assert!(!block.is_conditional());
+        assert!(!block.is_unconditional_jump());
+        assert!(block.is_return());
+    }
+
+    #[test]
+    fn instruction_and_terminator_accessors() {
+        let mut block = BasicBlock::new();
+        assert!(block.instructions().is_empty());
+        assert_eq!(block.terminator(), None);
+
+        let instruction0 = Id::test_new(0);
+        let instruction1 = Id::test_new(1);
+        block.insert_instruction(instruction0);
+        block.insert_instruction(instruction1);
+        assert_eq!(block.instructions(), &[instruction0, instruction1]);
+
+        block.instructions_mut().retain(|instruction| *instruction != instruction0);
+        assert_eq!(block.instructions(), &[instruction1]);
+
+        let terminator = TerminatorInstruction::Return { return_values: vec![Id::test_new(2)] };
+        block.set_terminator(terminator.clone());
+        assert_eq!(block.terminator(), Some(&terminator));
+    }
+
+    #[test]
+    fn defined_values() {
+        let mut dfg = DataFlowGraph::default();
+        let block = dfg.make_block();
+        let v0 = dfg.add_block_parameter(block, Type::unsigned(1));
+        let v1 = dfg.add_block_parameter(block, Type::field());
+
+        let not = dfg.make_instruction(Instruction::Not(v0), None);
+        dfg[block].insert_instruction(not);
+        let v2 = dfg.instruction_results(not)[0];
+
+        assert_eq!(dfg[block].defined_values(&dfg), vec![v0, v1, v2]);
+    }
+
+    #[test]
+    fn metadata() {
+        let mut block = BasicBlock::new();
+        assert_eq!(block.get_meta("loop_depth"), None);
+
+        block.set_meta("loop_depth", "2");
+        block.set_meta("cold", "true");
+        block.set_meta("loop_depth", "1");
+        assert_eq!(block.get_meta("loop_depth"), Some("1"));
+        assert_eq!(block.get_meta("cold"), Some("true"));
+
+        let copy = block.clone();
+        assert_eq!(copy.metadata(), block.metadata());
+    }
+}
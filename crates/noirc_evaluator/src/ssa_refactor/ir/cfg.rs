@@ -1,8 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 
 use super::{
     basic_block::{BasicBlock, BasicBlockId},
+    dom::DominatorTree,
+    errors::InternalError,
     function::Function,
+    loops::NaturalLoop,
 };
 
 /// A container for the successors and predecessors of some Block.
@@ -15,6 +18,10 @@ struct CfgNode {
     /// Set of blocks that are the targets of jumps in this block.
     /// The successors set has no meaningful order.
     pub(crate) successors: HashSet<BasicBlockId>,
+
+    /// The number of edges from this block to each of its successors. This is only greater
+    /// than 1 when a jmpif jumps to the same block from both of its arms.
+    pub(crate) edge_counts: HashMap<BasicBlockId, usize>,
 }
 
 /// The Control Flow Graph maintains a mapping of blocks to their predecessors
@@ -22,29 +29,130 @@ struct CfgNode {
 /// basic blocks.
 pub(crate) struct ControlFlowGraph {
     data: HashMap<BasicBlockId, CfgNode>,
+
+    /// The maximum number of successors any one block may have.
+    max_successors: usize,
+
+    /// The maximum number of predecessors any one block may have.
+    max_predecessors: usize,
 }
 
+/// The number of times `ControlFlowGraph::estimate_frequencies` assumes each loop iterates.
+const ESTIMATED_LOOP_TRIP_COUNT: f64 = 10.0;
+
+/// The successor bound used by `ControlFlowGraph::with_function`. No terminator instruction
+/// currently targets more than two blocks.
+const DEFAULT_MAX_SUCCESSORS: usize = 2;
+
+/// The predecessor bound used by `ControlFlowGraph::with_function` and
+/// `ControlFlowGraph::with_function_bounded`. Flattening expects each join block to merge at
+/// most two branches.
+const DEFAULT_MAX_PREDECESSORS: usize = 2;
+
 impl ControlFlowGraph {
     /// Allocate and compute the control flow graph for `func`.
     pub(crate) fn with_function(func: &Function) -> Self {
+        Self::with_function_bounded(func, DEFAULT_MAX_SUCCESSORS)
+    }
+
+    /// Allocate and compute the control flow graph for `func`, panicking if any block has more
+    /// than `max_successors` successors.
+    pub(crate) fn with_function_bounded(func: &Function, max_successors: usize) -> Self {
+        Self::with_function_bounds(func, max_successors, DEFAULT_MAX_PREDECESSORS)
+    }
+
+    /// Allocate and compute the control flow graph for `func`, panicking if any block has more
+    /// than `max_successors` successors or more than `max_predecessors` predecessors.
+    ///
+    /// This is only needed for functions which have not yet been shaped for flattening, such
+    /// as those with loop headers entered from several blocks.
+    pub(crate) fn with_function_bounds(
+        func: &Function,
+        max_successors: usize,
+        max_predecessors: usize,
+    ) -> Self {
         // It is expected to be safe to query the control flow graph for any reachable block,
         // therefore we must ensure that a node exists for the entry block, regardless of whether
         // it later comes to describe any edges after calling compute.
         let entry_block = func.entry_block();
-        let empty_node = CfgNode { predecessors: HashSet::new(), successors: HashSet::new() };
-        let data = HashMap::from([(entry_block, empty_node)]);
+        let data = HashMap::from([(entry_block, CfgNode::default())]);
 
-        let mut cfg = ControlFlowGraph { data };
+        let mut cfg = ControlFlowGraph { data, max_successors, max_predecessors };
         cfg.compute(func);
         cfg
     }
 
+    /// Returns the subgraph induced by `blocks`: a cfg containing each of `blocks` which is
+    /// within this cfg, and each edge of this cfg whose endpoints are both in `blocks`.
+    ///
+    /// This allows generic cfg algorithms to be run over part of a function, such as a loop.
+    pub(crate) fn induced_subgraph(&self, blocks: &HashSet<BasicBlockId>) -> ControlFlowGraph {
+        let data = self
+            .data
+            .iter()
+            .filter(|(block, _)| blocks.contains(block))
+            .map(|(block, node)| {
+                let node = CfgNode {
+                    predecessors: node.predecessors.intersection(blocks).copied().collect(),
+                    successors: node.successors.intersection(blocks).copied().collect(),
+                    edge_counts: node
+                        .edge_counts
+                        .iter()
+                        .filter(|(successor, _)| blocks.contains(successor))
+                        .map(|(successor, count)| (*successor, *count))
+                        .collect(),
+                };
+                (*block, node)
+            })
+            .collect();
+
+        let subgraph = ControlFlowGraph {
+            data,
+            max_successors: self.max_successors,
+            max_predecessors: self.max_predecessors,
+        };
+        subgraph.debug_verify();
+        subgraph
+    }
+
+    /// Returns a cfg with the direction of every edge reversed, such that each block's
+    /// predecessors become its successors. Post-dominators are the dominators of this cfg.
+    ///
+    /// The successor and predecessor bounds are swapped to match.
+    pub(crate) fn reversed(&self) -> ControlFlowGraph {
+        let data = self
+            .data
+            .iter()
+            .map(|(&block, node)| {
+                let node = CfgNode {
+                    predecessors: node.successors.clone(),
+                    successors: node.predecessors.clone(),
+                    edge_counts: node
+                        .predecessors
+                        .iter()
+                        .map(|&predecessor| (predecessor, self.edge_count(predecessor, block)))
+                        .collect(),
+                };
+                (block, node)
+            })
+            .collect();
+
+        let reversed = ControlFlowGraph {
+            data,
+            max_successors: self.max_predecessors,
+            max_predecessors: self.max_successors,
+        };
+        reversed.debug_verify();
+        reversed
+    }
+
     /// Compute all of the edges between each reachable block in the function
     fn compute(&mut self, func: &Function) {
         for basic_block_id in func.reachable_blocks() {
             let basic_block = &func.dfg[basic_block_id];
             self.compute_block(basic_block_id, basic_block);
         }
+        self.debug_verify();
     }
 
     /// Compute all of the edges for the current block given
@@ -63,6 +171,7 @@ impl ControlFlowGraph {
             .expect("ICE: Attempted to invalidate cfg node successors for non-existent node.");
 
         let old_successors = std::mem::take(&mut node.successors);
+        node.edge_counts.clear();
 
         for successor_id in old_successors {
             self.data
@@ -81,20 +190,90 @@ impl ControlFlowGraph {
         self.invalidate_block_successors(basic_block_id);
         let basic_block = &func.dfg[basic_block_id];
         self.compute_block(basic_block_id, basic_block);
+        self.debug_verify();
+    }
+
+    /// Asserts, in debug builds only, that every successor relationship in the graph has a
+    /// matching predecessor relationship and vice versa.
+    ///
+    /// This runs after each (re)computation of edges so that a corrupted graph is caught where
+    /// it was corrupted rather than by whichever pass next queries it.
+    fn debug_verify(&self) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+        for (&block, node) in &self.data {
+            for successor in &node.successors {
+                assert!(
+                    matches!(self.data.get(successor), Some(n) if n.predecessors.contains(&block)),
+                    "ICE: {successor} is a successor of {block}, but {block} is not its predecessor"
+                );
+            }
+            for predecessor in &node.predecessors {
+                assert!(
+                    matches!(self.data.get(predecessor), Some(n) if n.successors.contains(&block)),
+                    "ICE: {predecessor} is a predecessor of {block}, but {block} is not its successor"
+                );
+            }
+        }
+    }
+
+    /// Inserts `to` as a successor of `from` without the matching predecessor relationship.
+    #[cfg(test)]
+    fn corrupt_successors(&mut self, from: BasicBlockId, to: BasicBlockId) {
+        self.data.entry(from).or_default().successors.insert(to);
+        self.data.entry(to).or_default();
+    }
+
+    /// Remove a single directed edge from `from` to `to`, for use after changing `from`'s
+    /// terminator so that it targets `to` one fewer time. This is cheaper than recomputing
+    /// every edge of `from` with `recompute_block`.
+    ///
+    /// `to` only stops being a successor of `from` once its last edge is removed, as a jmpif
+    /// may target the same block from both of its arms. Errors if there is no such edge.
+    pub(crate) fn remove_edge(
+        &mut self,
+        from: BasicBlockId,
+        to: BasicBlockId,
+    ) -> Result<(), InternalError> {
+        let node = self.data.get_mut(&from).ok_or(InternalError::EdgeNotInCfg { from, to })?;
+        let count =
+            node.edge_counts.get_mut(&to).ok_or(InternalError::EdgeNotInCfg { from, to })?;
+
+        *count -= 1;
+        if *count == 0 {
+            node.edge_counts.remove(&to);
+            node.successors.remove(&to);
+            self.data
+                .get_mut(&to)
+                .expect("ICE: Cfg node successor doesn't exist.")
+                .predecessors
+                .remove(&from);
+        }
+        self.debug_verify();
+        Ok(())
     }
 
     /// Add a directed edge making `from` a predecessor of `to`.
     fn add_edge(&mut self, from: BasicBlockId, to: BasicBlockId) {
+        let max_successors = self.max_successors;
         let predecessor_node = self.data.entry(from).or_default();
+        // Jumping to an existing successor again (e.g. a jmpif with identical arms) only
+        // increases the edge count, so it never exceeds the successor bound
         assert!(
-            predecessor_node.successors.len() < 2,
-            "ICE: A cfg node cannot have more than two successors"
+            predecessor_node.successors.contains(&to)
+                || predecessor_node.successors.len() < max_successors,
+            "ICE: A cfg node cannot have more than {max_successors} successors"
         );
         predecessor_node.successors.insert(to);
+        *predecessor_node.edge_counts.entry(to).or_default() += 1;
+
+        let max_predecessors = self.max_predecessors;
         let successor_node = self.data.entry(to).or_default();
         assert!(
-            successor_node.predecessors.len() < 2,
-            "ICE: A cfg node cannot have more than two predecessors"
+            successor_node.predecessors.contains(&from)
+                || successor_node.predecessors.len() < max_predecessors,
+            "ICE: A cfg node cannot have more than {max_predecessors} predecessors"
         );
         successor_node.predecessors.insert(from);
     }
@@ -124,13 +303,432 @@ impl ControlFlowGraph {
             .iter()
             .copied()
     }
+
+    /// Returns the CFG predecessors of `basic_block_id`, sorted by block id.
+    ///
+    /// Unlike `predecessors`, the order is deterministic.
+    pub(crate) fn predecessors_sorted(&self, basic_block_id: BasicBlockId) -> Vec<BasicBlockId> {
+        let mut predecessors: Vec<_> = self.predecessors(basic_block_id).collect();
+        predecessors.sort();
+        predecessors
+    }
+
+    /// Returns the CFG successors of `basic_block_id`, sorted by block id.
+    ///
+    /// Unlike `successors`, the order is deterministic.
+    pub(crate) fn successors_sorted(&self, basic_block_id: BasicBlockId) -> Vec<BasicBlockId> {
+        let mut successors: Vec<_> = self.successors(basic_block_id).collect();
+        successors.sort();
+        successors
+    }
+
+    /// Returns the maximum number of predecessors any one block may have.
+    pub(crate) fn max_predecessors(&self) -> usize {
+        self.max_predecessors
+    }
+
+    /// Returns the number of edges from `from` to `to`.
+    ///
+    /// Unlike `successors`, this counts a jmpif whose arms both jump to `to` as two edges.
+    pub(crate) fn edge_count(&self, from: BasicBlockId, to: BasicBlockId) -> usize {
+        self.data
+            .get(&from)
+            .expect("ICE: Attempted to count edges of block not found within cfg.")
+            .edge_counts
+            .get(&to)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the successor of `block` if it has exactly one, or None otherwise.
+    ///
+    /// Errors if `block` is not within the cfg.
+    pub(crate) fn sole_successor(
+        &self,
+        block: BasicBlockId,
+    ) -> Result<Option<BasicBlockId>, InternalError> {
+        let node = self.data.get(&block).ok_or(InternalError::BlockNotInCfg { block })?;
+        if node.successors.len() == 1 {
+            Ok(node.successors.iter().next().copied())
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the predecessor of `block` if it has exactly one, or None otherwise.
+    ///
+    /// Errors if `block` is not within the cfg.
+    pub(crate) fn sole_predecessor(
+        &self,
+        block: BasicBlockId,
+    ) -> Result<Option<BasicBlockId>, InternalError> {
+        let node = self.data.get(&block).ok_or(InternalError::BlockNotInCfg { block })?;
+        if node.predecessors.len() == 1 {
+            Ok(node.predecessors.iter().next().copied())
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Returns the minimum number of edges from each block to any exit block: a block with no
+    /// successors, which for a finished function is a block ending in a return.
+    ///
+    /// Blocks which cannot reach an exit block are omitted.
+    pub(crate) fn distances_to_exit(&self) -> HashMap<BasicBlockId, u32> {
+        let mut distances = HashMap::new();
+        let mut queue = VecDeque::new();
+        for (block, node) in &self.data {
+            if node.successors.is_empty() {
+                distances.insert(*block, 0);
+                queue.push_back(*block);
+            }
+        }
+
+        // Searching backwards from every exit at once visits each block first along its
+        // shortest path to the nearest exit.
+        while let Some(block) = queue.pop_front() {
+            let distance = distances[&block] + 1;
+            for predecessor in self.predecessors(block) {
+                if let Entry::Vacant(entry) = distances.entry(predecessor) {
+                    entry.insert(distance);
+                    queue.push_back(predecessor);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Returns true if control can flow from `from` to `to` along one or more edges.
+    ///
+    /// A block only reaches itself if it is part of a cycle. Errors if either block is not
+    /// within the cfg.
+    pub(crate) fn reachable_from(
+        &self,
+        from: BasicBlockId,
+        to: BasicBlockId,
+    ) -> Result<bool, InternalError> {
+        for block in [from, to] {
+            if !self.data.contains_key(&block) {
+                return Err(InternalError::BlockNotInCfg { block });
+            }
+        }
+
+        let mut visited = HashSet::new();
+        let mut queue: VecDeque<_> = self.successors(from).collect();
+        while let Some(block) = queue.pop_front() {
+            if block == to {
+                return Ok(true);
+            }
+            if visited.insert(block) {
+                queue.extend(self.successors(block));
+            }
+        }
+        Ok(false)
+    }
+
+    /// Calls `visitor` once for each block reachable from `start`, including `start` itself, in
+    /// depth-first preorder. Successors are visited in ascending block order.
+    ///
+    /// Each block is visited at most once, so this terminates on cyclic cfgs.
+    pub(crate) fn visit_dfs<F: FnMut(BasicBlockId)>(&self, start: BasicBlockId, mut visitor: F) {
+        let mut visited = HashSet::new();
+        let mut stack = vec![start];
+        while let Some(block) = stack.pop() {
+            if !visited.insert(block) {
+                continue;
+            }
+            visitor(block);
+            let successors = self.successors_sorted(block);
+            stack.extend(successors.into_iter().rev().filter(|block| !visited.contains(block)));
+        }
+    }
+
+    /// Returns each block within the cfg which cannot be reached from `entry`, along with why,
+    /// sorted by block id.
+    ///
+    /// Blocks only enter the cfg through its edges, so this reports blocks which became
+    /// unreachable after the cfg was computed, e.g. when a jump to them was rewritten.
+    pub(crate) fn unreachable_report(&self, entry: BasicBlockId) -> Vec<UnreachableBlock> {
+        let mut reachable = HashSet::new();
+        self.visit_dfs(entry, |block| {
+            reachable.insert(block);
+        });
+
+        let mut report: Vec<_> = self
+            .data
+            .iter()
+            .filter(|(block, _)| !reachable.contains(block))
+            .map(|(&block, node)| {
+                let reason = if node.predecessors.is_empty() {
+                    UnreachableReason::NoPredecessors
+                } else {
+                    UnreachableReason::UnreachablePredecessors
+                };
+                UnreachableBlock { block, reason }
+            })
+            .collect();
+        report.sort_by_key(|unreachable| unreachable.block);
+        report
+    }
+
+    /// Heuristically estimates how often each block reachable according to `dom` executes,
+    /// relative to one execution of the function.
+    ///
+    /// The entry block has a frequency of 1.0 and each block's frequency is split evenly across
+    /// the edges leaving it, with two exceptions for loops. A loop header's frequency is
+    /// multiplied by an assumed trip count of `ESTIMATED_LOOP_TRIP_COUNT`, and the edges leaving
+    /// a loop instead share the frequency with which the loop is entered, so that blocks after
+    /// a loop are not inflated by its trip count. No profiling or branch hints are consulted,
+    /// so these figures are only suitable for guiding heuristics such as block layout.
+    pub(crate) fn estimate_frequencies(&self, dom: &DominatorTree) -> HashMap<BasicBlockId, f64> {
+        // The blocks of each loop, keyed by header. Loops sharing a header are merged.
+        let mut loops: HashMap<BasicBlockId, HashSet<BasicBlockId>> = HashMap::new();
+        for (latch, header) in self.back_edges(dom) {
+            let natural_loop = NaturalLoop::with_back_edge(header, latch, self);
+            loops.entry(header).or_default().extend(natural_loop.blocks());
+        }
+
+        // Returns the header of the outermost loop which the edge `from -> to` leaves, if any.
+        let exited_loop = |from: BasicBlockId, to: BasicBlockId| {
+            loops
+                .iter()
+                .filter(|(_, blocks)| blocks.contains(&from) && !blocks.contains(&to))
+                .max_by_key(|(_, blocks)| blocks.len())
+                .map(|(header, _)| *header)
+        };
+
+        let mut exit_edge_counts: HashMap<BasicBlockId, usize> = HashMap::new();
+        for (&from, node) in &self.data {
+            if !dom.is_reachable(from) {
+                continue;
+            }
+            for (&to, &count) in &node.edge_counts {
+                if let Some(header) = exited_loop(from, to) {
+                    *exit_edge_counts.entry(header).or_default() += count;
+                }
+            }
+        }
+
+        let mut blocks: Vec<_> =
+            self.data.keys().copied().filter(|block| dom.is_reachable(*block)).collect();
+        blocks.sort_by(|a, b| dom.reverse_post_order_cmp(*a, *b));
+
+        let mut frequencies = HashMap::new();
+        // The frequency with which each loop is entered, keyed by header
+        let mut entry_frequencies = HashMap::new();
+        for block in blocks {
+            let mut frequency = if dom.immediate_dominator(block).is_none() { 1.0 } else { 0.0 };
+
+            for predecessor in self.predecessors(block) {
+                // Back-edges are accounted for by the trip count below
+                if !dom.is_reachable(predecessor) || dom.dominates_helper(block, predecessor) {
+                    continue;
+                }
+                // Predecessors along forward edges precede `block` in reverse post-order
+                let edge_count = self.edge_count(predecessor, block) as f64;
+                frequency += match exited_loop(predecessor, block) {
+                    Some(header) => {
+                        let entry_frequency =
+                            entry_frequencies.get(&header).copied().unwrap_or(0.0);
+                        entry_frequency * edge_count / exit_edge_counts[&header] as f64
+                    }
+                    None => {
+                        let remaining_edges: usize = self.data[&predecessor]
+                            .edge_counts
+                            .iter()
+                            .filter(|(to, _)| exited_loop(predecessor, **to).is_none())
+                            .map(|(_, count)| count)
+                            .sum();
+                        let predecessor_frequency =
+                            frequencies.get(&predecessor).copied().unwrap_or(0.0);
+                        predecessor_frequency * edge_count / remaining_edges as f64
+                    }
+                };
+            }
+
+            if loops.contains_key(&block) {
+                entry_frequencies.insert(block, frequency);
+                frequency *= ESTIMATED_LOOP_TRIP_COUNT;
+            }
+            frequencies.insert(block, frequency);
+        }
+        frequencies
+    }
+
+    /// Returns true if removing `block` would leave some other block unreachable from `entry`.
+    ///
+    /// Reachability is only measured from `entry`, so blocks which are already unreachable
+    /// from it neither count as articulation points nor mask them. Removing `entry` itself
+    /// strands every other block it reaches. Errors if `entry` or `block` is not within the cfg.
+    pub(crate) fn is_articulation(
+        &self,
+        entry: BasicBlockId,
+        block: BasicBlockId,
+    ) -> Result<bool, InternalError> {
+        for block in [entry, block] {
+            if !self.data.contains_key(&block) {
+                return Err(InternalError::BlockNotInCfg { block });
+            }
+        }
+
+        let reachable = self.reachable_excluding(entry, None);
+        let reachable_without_block = self.reachable_excluding(entry, Some(block));
+        Ok(reachable
+            .iter()
+            .any(|other| *other != block && !reachable_without_block.contains(other)))
+    }
+
+    /// Returns the blocks reachable from `entry`, never passing through `excluded`.
+    fn reachable_excluding(
+        &self,
+        entry: BasicBlockId,
+        excluded: Option<BasicBlockId>,
+    ) -> HashSet<BasicBlockId> {
+        if Some(entry) == excluded {
+            return HashSet::new();
+        }
+
+        let mut queue = VecDeque::from([entry]);
+        let mut reachable = HashSet::from([entry]);
+        while let Some(block) = queue.pop_front() {
+            for successor in self.successors(block) {
+                if Some(successor) != excluded && reachable.insert(successor) {
+                    queue.push_back(successor);
+                }
+            }
+        }
+        reachable
+    }
+
+    /// Returns every edge `from -> to` whose target dominates its source, sorted by source block.
+    ///
+    /// These are the back-edges of the function's loops, with `to` being the loop header.
+    /// Edges between blocks unreachable from the entry block are never reported.
+    pub(crate) fn back_edges(&self, dom: &DominatorTree) -> Vec<(BasicBlockId, BasicBlockId)> {
+        let mut back_edges = Vec::new();
+        for (&from, node) in &self.data {
+            if !dom.is_reachable(from) {
+                continue;
+            }
+            for &to in &node.successors {
+                if dom.is_reachable(to) && dom.dominates_helper(to, from) {
+                    back_edges.push((from, to));
+                }
+            }
+        }
+        back_edges.sort();
+        back_edges
+    }
+
+    /// Returns the number of blocks on the longest path through the cfg, starting from a block
+    /// without predecessors and ending at one without successors.
+    ///
+    /// Each cycle is collapsed into a single strongly connected component counting for all of
+    /// its blocks, so loops contribute their size once rather than making paths unbounded.
+    /// Errors if the cfg contains no blocks.
+    pub(crate) fn longest_path_len(&self) -> Result<usize, InternalError> {
+        let components = self.strongly_connected_components();
+        let mut component_of = HashMap::new();
+        for (index, component) in components.iter().enumerate() {
+            for block in component {
+                component_of.insert(*block, index);
+            }
+        }
+
+        // Components are in topological order, so each predecessor's length is already known
+        let mut lengths: Vec<usize> = Vec::with_capacity(components.len());
+        for (index, component) in components.iter().enumerate() {
+            let longest_predecessor = component
+                .iter()
+                .flat_map(|block| self.predecessors(*block))
+                .map(|predecessor| component_of[&predecessor])
+                .filter(|predecessor| *predecessor != index)
+                .map(|predecessor| lengths[predecessor])
+                .max()
+                .unwrap_or(0);
+            lengths.push(longest_predecessor + component.len());
+        }
+        lengths.into_iter().max().ok_or(InternalError::EmptyCfg)
+    }
+
+    /// Returns the strongly connected components of the cfg in topological order, such that
+    /// every edge between two components leads from an earlier component to a later one.
+    ///
+    /// This uses Kosaraju's algorithm: blocks are ordered by when a depth-first search over
+    /// successors finishes with them, and components are then gathered by searching over
+    /// predecessors in reverse of that order.
+    fn strongly_connected_components(&self) -> Vec<Vec<BasicBlockId>> {
+        let mut blocks: Vec<_> = self.data.keys().copied().collect();
+        blocks.sort();
+
+        let mut visited = HashSet::new();
+        let mut finished = Vec::with_capacity(blocks.len());
+        for root in &blocks {
+            let mut stack = vec![(*root, false)];
+            while let Some((block, children_done)) = stack.pop() {
+                if children_done {
+                    finished.push(block);
+                } else if visited.insert(block) {
+                    stack.push((block, true));
+                    let successors = self.successors_sorted(block);
+                    stack.extend(successors.into_iter().map(|successor| (successor, false)));
+                }
+            }
+        }
+
+        let mut assigned = HashSet::new();
+        let mut components = Vec::new();
+        for root in finished.into_iter().rev() {
+            if !assigned.insert(root) {
+                continue;
+            }
+            let mut component = vec![root];
+            let mut stack = vec![root];
+            while let Some(block) = stack.pop() {
+                for predecessor in self.predecessors(block) {
+                    if assigned.insert(predecessor) {
+                        component.push(predecessor);
+                        stack.push(predecessor);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+        components
+    }
+}
+
+/// A block which cannot be reached from the entry block, as returned by
+/// `ControlFlowGraph::unreachable_report`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct UnreachableBlock {
+    pub(crate) block: BasicBlockId,
+    pub(crate) reason: UnreachableReason,
+}
+
+/// Why a block cannot be reached from the entry block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnreachableReason {
+    /// No block jumps to the block.
+    NoPredecessors,
+    /// The block is only jumped to by blocks which are themselves unreachable.
+    UnreachablePredecessors,
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::ssa_refactor::ir::{instruction::TerminatorInstruction, map::Id, types::Type};
+    use std::collections::HashSet;
 
-    use super::{super::function::Function, ControlFlowGraph};
+    use crate::ssa_refactor::ir::{
+        dom::DominatorTree, errors::InternalError, instruction::TerminatorInstruction, map::Id,
+        post_order::PostOrder, types::Type,
+    };
+
+    use super::{
+        super::{basic_block::BasicBlockId, function::Function},
+        ControlFlowGraph, UnreachableBlock, UnreachableReason,
+    };
 
     #[test]
     fn empty() {
@@ -262,4 +860,768 @@ mod tests {
             assert!(block2_successors.contains(&ret_block_id));
         }
     }
+
+    #[test]
+    fn back_edges() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block2, else: block1
+        //   block1():
+        //     jmpif cond, then: block1, else: block2
+        //   block2():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block2_id,
+            else_destination: block1_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let cfg = ControlFlowGraph::with_function(&func);
+        let post_order = PostOrder::with_function(&func);
+        let dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
+
+        // The self-loop on block1 is the only back-edge
+        assert_eq!(cfg.back_edges(&dom_tree), vec![(block1_id, block1_id)]);
+    }
+
+    #[test]
+    fn within_successor_bound() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block1
+        //   block1():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block1_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        // Both arms target the same block so block0 only has a single successor
+        let cfg = ControlFlowGraph::with_function_bounded(&func, 1);
+        assert_eq!(cfg.successors(block0_id).len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "ICE: A cfg node cannot have more than 1 successors")]
+    fn exceeds_successor_bound() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block2
+        //   block1():
+        //     return ()
+        //   block2():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        // Within the default bound of two successors
+        ControlFlowGraph::with_function(&func);
+        ControlFlowGraph::with_function_bounded(&func, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "ICE: A cfg node cannot have more than 2 predecessors")]
+    fn exceeds_predecessor_bound() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block2
+        //   block1():
+        //     jmpif cond, then: block2, else: block3
+        //   block2():
+        //     jmp block3()
+        //   block3():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block2_id,
+            else_destination: block3_id,
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block3_id,
+            arguments: vec![],
+        });
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        // Each block has at most two predecessors
+        let cfg = ControlFlowGraph::with_function(&func);
+        assert_eq!(cfg.predecessors(block3_id).len(), 2);
+
+        // Also jumping to block3 from block0 gives it a third predecessor
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block3_id,
+        });
+        let cfg = ControlFlowGraph::with_function_bounds(&func, 2, 3);
+        assert_eq!(cfg.predecessors(block3_id).len(), 3);
+        ControlFlowGraph::with_function(&func);
+    }
+
+    #[test]
+    fn reachable_from() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block2, else: block1
+        //   block1():
+        //     jmpif cond, then: block1, else: block2
+        //   block2():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block2_id,
+            else_destination: block1_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let cfg = ControlFlowGraph::with_function(&func);
+
+        assert_eq!(cfg.reachable_from(block0_id, block2_id), Ok(true));
+        assert_eq!(cfg.reachable_from(block0_id, block1_id), Ok(true));
+        for block in [block0_id, block1_id, block2_id] {
+            assert_eq!(cfg.reachable_from(block2_id, block), Ok(false));
+        }
+
+        // Only blocks within a cycle reach themselves
+        assert_eq!(cfg.reachable_from(block0_id, block0_id), Ok(false));
+        assert_eq!(cfg.reachable_from(block1_id, block1_id), Ok(true));
+
+        let missing_block = func.dfg.make_block();
+        assert_eq!(
+            cfg.reachable_from(block0_id, missing_block),
+            Err(InternalError::BlockNotInCfg { block: missing_block })
+        );
+    }
+
+    #[test]
+    fn sole_successor() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block2
+        //   block1():
+        //     jmp block2()
+        //   block2():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block2_id,
+            arguments: vec![],
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let cfg = ControlFlowGraph::with_function(&func);
+
+        assert_eq!(cfg.sole_successor(block0_id), Ok(None));
+        assert_eq!(cfg.sole_successor(block1_id), Ok(Some(block2_id)));
+        assert_eq!(cfg.sole_successor(block2_id), Ok(None));
+
+        let missing_block = func.dfg.make_block();
+        assert_eq!(
+            cfg.sole_successor(missing_block),
+            Err(InternalError::BlockNotInCfg { block: missing_block })
+        );
+    }
+
+    #[test]
+    fn edge_count() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block1
+        //   block1():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block1_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let mut cfg = ControlFlowGraph::with_function(&func);
+
+        assert_eq!(cfg.successors(block0_id).len(), 1);
+        assert_eq!(cfg.edge_count(block0_id, block1_id), 2);
+        assert_eq!(cfg.edge_count(block1_id, block0_id), 0);
+
+        // Recomputing the block must not accumulate stale edges
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block1_id,
+            arguments: vec![],
+        });
+        cfg.recompute_block(&func, block0_id);
+        assert_eq!(cfg.edge_count(block0_id, block1_id), 1);
+    }
+
+    /// Builds the modified function from the `jumps` test, returning it along with the ids of
+    /// block0, block1, block2, and ret_block:
+    ///
+    /// fn func {
+    ///   block0(cond: u1):
+    ///     jmpif cond, then: block1, else: ret_block
+    ///   block1():
+    ///     jmpif cond, then: block1, else: block2
+    ///   block2():
+    ///     jmp ret_block()
+    ///   ret_block():
+    ///     return ()
+    /// }
+    fn modified_jumps() -> (Function, [BasicBlockId; 4]) {
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let ret_block_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: ret_block_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: ret_block_id,
+            arguments: vec![],
+        });
+        func.dfg[ret_block_id]
+            .set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        (func, [block0_id, block1_id, block2_id, ret_block_id])
+    }
+
+    #[test]
+    fn sole_predecessor() {
+        let (mut func, [block0_id, block1_id, block2_id, ret_block_id]) = modified_jumps();
+        let cfg = ControlFlowGraph::with_function(&func);
+
+        assert_eq!(cfg.sole_predecessor(block0_id), Ok(None));
+        // block1 is a join of block0 and itself, as is ret_block of block0 and block2
+        assert_eq!(cfg.sole_predecessor(block1_id), Ok(None));
+        assert_eq!(cfg.sole_predecessor(ret_block_id), Ok(None));
+        assert_eq!(cfg.sole_predecessor(block2_id), Ok(Some(block1_id)));
+
+        let missing_block = func.dfg.make_block();
+        assert_eq!(
+            cfg.sole_predecessor(missing_block),
+            Err(InternalError::BlockNotInCfg { block: missing_block })
+        );
+    }
+
+    #[test]
+    fn sorted_neighbours() {
+        let (func, [block0_id, block1_id, block2_id, ret_block_id]) = modified_jumps();
+        let cfg = ControlFlowGraph::with_function(&func);
+
+        assert_eq!(cfg.predecessors_sorted(ret_block_id), vec![block0_id, block2_id]);
+        assert_eq!(cfg.predecessors_sorted(block1_id), vec![block0_id, block1_id]);
+        assert_eq!(cfg.successors_sorted(block0_id), vec![block1_id, ret_block_id]);
+        assert_eq!(cfg.successors_sorted(block1_id), vec![block1_id, block2_id]);
+        assert!(cfg.successors_sorted(ret_block_id).is_empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "is a successor of")]
+    fn debug_verify_detects_missing_predecessor() {
+        let (func, [_, block1_id, _, ret_block_id]) = modified_jumps();
+        let mut cfg = ControlFlowGraph::with_function(&func);
+        cfg.debug_verify();
+
+        cfg.corrupt_successors(ret_block_id, block1_id);
+        cfg.debug_verify();
+    }
+
+    #[test]
+    fn estimate_frequencies() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block2
+        //   block1():
+        //     jmp block3()
+        //   block2():
+        //     jmp block3()
+        //   block3():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        for block in [block1_id, block2_id] {
+            func.dfg[block].set_terminator(TerminatorInstruction::Jmp {
+                destination: block3_id,
+                arguments: vec![],
+            });
+        }
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let cfg = ControlFlowGraph::with_function(&func);
+        let dom_tree = DominatorTree::with_function(&func);
+
+        let frequencies = cfg.estimate_frequencies(&dom_tree);
+        let expected = [(block0_id, 1.0), (block1_id, 0.5), (block2_id, 0.5), (block3_id, 1.0)];
+        assert_eq!(frequencies.len(), expected.len());
+        for (block, frequency) in expected {
+            assert!((frequencies[&block] - frequency).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn estimate_loop_frequencies() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmp block1()
+        //   block1():
+        //     jmpif cond, then: block2, else: block3
+        //   block2():
+        //     jmp block1()
+        //   block3():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+
+        for (block, destination) in [(block0_id, block1_id), (block2_id, block1_id)] {
+            func.dfg[block]
+                .set_terminator(TerminatorInstruction::Jmp { destination, arguments: vec![] });
+        }
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block2_id,
+            else_destination: block3_id,
+        });
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let cfg = ControlFlowGraph::with_function(&func);
+        let dom_tree = DominatorTree::with_function(&func);
+
+        let frequencies = cfg.estimate_frequencies(&dom_tree);
+        let expected = [(block0_id, 1.0), (block1_id, 10.0), (block2_id, 10.0), (block3_id, 1.0)];
+        assert_eq!(frequencies.len(), expected.len());
+        for (block, frequency) in expected {
+            assert!((frequencies[&block] - frequency).abs() < 1e-9);
+        }
+        // Leaving the loop must not carry its trip count into the blocks after it
+        assert!((frequencies[&block3_id] - frequencies[&block0_id]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn is_articulation() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmp block1()
+        //   block1():
+        //     jmpif cond, then: block2, else: block3
+        //   block2():
+        //     jmp block4()
+        //   block3():
+        //     jmp block4()
+        //   block4():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+        let block4_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block1_id,
+            arguments: vec![],
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block2_id,
+            else_destination: block3_id,
+        });
+        for block in [block2_id, block3_id] {
+            func.dfg[block].set_terminator(TerminatorInstruction::Jmp {
+                destination: block4_id,
+                arguments: vec![],
+            });
+        }
+        func.dfg[block4_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let cfg = ControlFlowGraph::with_function(&func);
+        let entry = func.entry_block();
+
+        // Every path to the diamond goes through block1, but either arm of it can be bypassed
+        assert_eq!(cfg.is_articulation(entry, block0_id), Ok(true));
+        assert_eq!(cfg.is_articulation(entry, block1_id), Ok(true));
+        assert_eq!(cfg.is_articulation(entry, block2_id), Ok(false));
+        assert_eq!(cfg.is_articulation(entry, block3_id), Ok(false));
+        assert_eq!(cfg.is_articulation(entry, block4_id), Ok(false));
+
+        let missing_block = func.dfg.make_block();
+        assert_eq!(
+            cfg.is_articulation(entry, missing_block),
+            Err(InternalError::BlockNotInCfg { block: missing_block })
+        );
+    }
+
+    #[test]
+    fn is_articulation_from_loop_header_entry() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block2
+        //   block1():
+        //     jmp block0()
+        //   block2():
+        //     jmp block3()
+        //   block3():
+        //     return ()
+        //   block4():
+        //     jmp block3()
+        // }
+        //
+        // block4 starts out as the else arm of block0, and is left behind as a dead block
+        // without predecessors once block0 is retargeted to block2.
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+        let block4_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block4_id,
+        });
+        for (block, destination) in
+            [(block1_id, block0_id), (block2_id, block3_id), (block4_id, block3_id)]
+        {
+            func.dfg[block]
+                .set_terminator(TerminatorInstruction::Jmp { destination, arguments: vec![] });
+        }
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let mut cfg = ControlFlowGraph::with_function(&func);
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        cfg.recompute_block(&func, block0_id);
+        cfg.recompute_block(&func, block2_id);
+
+        // The entry has a predecessor, and the dead block4 bypasses block2 on the way to block3
+        let entry = func.entry_block();
+        assert_eq!(cfg.is_articulation(entry, block0_id), Ok(true));
+        assert_eq!(cfg.is_articulation(entry, block1_id), Ok(false));
+        assert_eq!(cfg.is_articulation(entry, block2_id), Ok(true));
+        assert_eq!(cfg.is_articulation(entry, block3_id), Ok(false));
+        assert_eq!(cfg.is_articulation(entry, block4_id), Ok(false));
+    }
+
+    #[test]
+    fn remove_edge() {
+        let (func, [block0_id, block1_id, block2_id, ret_block_id]) = modified_jumps();
+        let mut cfg = ControlFlowGraph::with_function(&func);
+
+        // Drop the self-loop on block1
+        assert_eq!(cfg.remove_edge(block1_id, block1_id), Ok(()));
+
+        assert_eq!(cfg.successors(block1_id).collect::<Vec<_>>(), vec![block2_id]);
+        assert_eq!(cfg.predecessors(block1_id).collect::<Vec<_>>(), vec![block0_id]);
+        assert_eq!(cfg.edge_count(block1_id, block1_id), 0);
+        assert_eq!(cfg.predecessors_sorted(ret_block_id), vec![block0_id, block2_id]);
+
+        assert_eq!(
+            cfg.remove_edge(block1_id, block1_id),
+            Err(InternalError::EdgeNotInCfg { from: block1_id, to: block1_id })
+        );
+        assert_eq!(
+            cfg.remove_edge(block0_id, block2_id),
+            Err(InternalError::EdgeNotInCfg { from: block0_id, to: block2_id })
+        );
+    }
+
+    #[test]
+    fn induced_subgraph() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmp block1()
+        //   block1():
+        //     jmpif cond, then: block2, else: block3
+        //   block2():
+        //     jmp block1()
+        //   block3():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block1_id,
+            arguments: vec![],
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block2_id,
+            else_destination: block3_id,
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block1_id,
+            arguments: vec![],
+        });
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let cfg = ControlFlowGraph::with_function(&func);
+        let loop_blocks = HashSet::from([block1_id, block2_id]);
+        let subgraph = cfg.induced_subgraph(&loop_blocks);
+
+        // Only the edges within the loop remain
+        assert_eq!(subgraph.successors_sorted(block1_id), vec![block2_id]);
+        assert_eq!(subgraph.predecessors_sorted(block1_id), vec![block2_id]);
+        assert_eq!(subgraph.successors_sorted(block2_id), vec![block1_id]);
+        assert_eq!(subgraph.predecessors_sorted(block2_id), vec![block1_id]);
+        assert_eq!(subgraph.edge_count(block1_id, block3_id), 0);
+
+        for block in [block0_id, block3_id] {
+            assert_eq!(
+                subgraph.reachable_from(block, block1_id),
+                Err(InternalError::BlockNotInCfg { block })
+            );
+        }
+    }
+
+    #[test]
+    fn distances_to_exit() {
+        let (func, [block0_id, block1_id, block2_id, ret_block_id]) = modified_jumps();
+        let cfg = ControlFlowGraph::with_function(&func);
+
+        let distances = cfg.distances_to_exit();
+        assert_eq!(distances.len(), 4);
+        assert_eq!(distances[&ret_block_id], 0);
+        assert_eq!(distances[&block0_id], 1);
+        assert_eq!(distances[&block2_id], 1);
+        assert_eq!(distances[&block1_id], 2);
+    }
+
+    #[test]
+    fn unreachable_report() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block3
+        //   block1():
+        //     jmp block2()
+        //   block2():
+        //     jmp block3()
+        //   block3():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block3_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block2_id,
+            arguments: vec![],
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block3_id,
+            arguments: vec![],
+        });
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let mut cfg = ControlFlowGraph::with_function(&func);
+        assert!(cfg.unreachable_report(block0_id).is_empty());
+
+        // Jumping straight to block3 orphans block1, which in turn strands block2
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block3_id,
+            arguments: vec![],
+        });
+        cfg.recompute_block(&func, block0_id);
+
+        assert_eq!(
+            cfg.unreachable_report(block0_id),
+            vec![
+                UnreachableBlock { block: block1_id, reason: UnreachableReason::NoPredecessors },
+                UnreachableBlock {
+                    block: block2_id,
+                    reason: UnreachableReason::UnreachablePredecessors
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn visit_dfs() {
+        let (func, [block0_id, block1_id, block2_id, ret_block_id]) = modified_jumps();
+        let cfg = ControlFlowGraph::with_function(&func);
+
+        // block1 jumps to itself, which must not cause it to be visited again
+        let mut visited = Vec::new();
+        cfg.visit_dfs(block0_id, |block| visited.push(block));
+        assert_eq!(visited, vec![block0_id, block1_id, block2_id, ret_block_id]);
+
+        let mut visited = Vec::new();
+        cfg.visit_dfs(block2_id, |block| visited.push(block));
+        assert_eq!(visited, vec![block2_id, ret_block_id]);
+    }
+
+    #[test]
+    fn longest_path_len() {
+        // fn func {
+        //   block0(cond: u1):
+        //     jmpif cond, then: block1, else: block2
+        //   block1():
+        //     jmp block3()
+        //   block2():
+        //     jmp block3()
+        //   block3():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        for block in [block1_id, block2_id] {
+            func.dfg[block].set_terminator(TerminatorInstruction::Jmp {
+                destination: block3_id,
+                arguments: vec![],
+            });
+        }
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let cfg = ControlFlowGraph::with_function(&func);
+        assert_eq!(cfg.longest_path_len(), Ok(3));
+
+        // block1 loops on itself, counting once along block0 -> block1 -> block2 -> ret_block
+        let (func, _) = modified_jumps();
+        let cfg = ControlFlowGraph::with_function(&func);
+        assert_eq!(cfg.longest_path_len(), Ok(4));
+
+        let empty = cfg.induced_subgraph(&HashSet::new());
+        assert_eq!(empty.longest_path_len(), Err(InternalError::EmptyCfg));
+    }
 }
@@ -70,6 +70,49 @@ impl DominatorTree {
         self.nodes.get(&block_id).and_then(|node| node.immediate_dominator)
     }
 
+    /// Returns every block within the tree, which are those reachable from its root, in no
+    /// particular order.
+    pub(crate) fn blocks(&self) -> impl Iterator<Item = BasicBlockId> + '_ {
+        self.nodes.keys().copied()
+    }
+
+    /// Returns the blocks which are guaranteed to execute before `block_id` on every path from
+    /// the entry block to it: its dominators, ordered from the entry block down to the immediate
+    /// dominator of `block_id`. `block_id` itself is excluded.
+    ///
+    /// This is empty if `block_id` is the entry block or is unreachable.
+    pub(crate) fn must_execute_before(&self, block_id: BasicBlockId) -> Vec<BasicBlockId> {
+        let mut dominators = Vec::new();
+        let mut current = self.immediate_dominator(block_id);
+        while let Some(dominator) = current {
+            dominators.push(dominator);
+            current = self.immediate_dominator(dominator);
+        }
+        dominators.reverse();
+        dominators
+    }
+
+    /// Returns true if the distinct blocks `a` and `b` share the same immediate dominator.
+    ///
+    /// The entry block and unreachable blocks have no immediate dominator, so are never
+    /// siblings of any block.
+    pub(crate) fn are_siblings(&self, a: BasicBlockId, b: BasicBlockId) -> bool {
+        a != b
+            && self.immediate_dominator(a).is_some()
+            && self.immediate_dominator(a) == self.immediate_dominator(b)
+    }
+
+    /// Returns the lowest block which dominates every block in `blocks`. A value used in each
+    /// of `blocks` may be safely hoisted or sunk to this block.
+    ///
+    /// This panics if `blocks` is empty, as there is no meaningful anchor for a value without
+    /// uses, or if any of the blocks are unreachable.
+    pub(crate) fn lowest_common_dominator_of(&self, blocks: &[BasicBlockId]) -> BasicBlockId {
+        let (first, rest) =
+            blocks.split_first().expect("Expected at least one block to find a dominator of");
+        rest.iter().fold(*first, |dominator, block| self.common_dominator(dominator, *block))
+    }
+
     /// Compare two blocks relative to the reverse post-order.
     pub(crate) fn reverse_post_order_cmp(&self, a: BasicBlockId, b: BasicBlockId) -> Ordering {
         match (self.nodes.get(&a), self.nodes.get(&b)) {
@@ -137,6 +180,27 @@ impl DominatorTree {
         Self::with_cfg_and_post_order(&cfg, &post_order)
     }
 
+    /// Allocate and compute the post-dominator tree of the given function: the dominator tree
+    /// of its reversed control flow graph, rooted at its exit block. Within this tree, a block
+    /// dominates another if every path from the other block to the exit passes through it.
+    ///
+    /// Returns `None` unless exactly one reachable block has no successors, as post-dominance
+    /// is only computed relative to a single exit block. Blocks which cannot reach the exit
+    /// block are unreachable within the tree.
+    pub(crate) fn with_function_post_dom(func: &Function) -> Option<Self> {
+        let cfg = ControlFlowGraph::with_function(func);
+        let mut exits =
+            func.reachable_blocks().into_iter().filter(|block| cfg.successors(*block).len() == 0);
+        let exit = exits.next()?;
+        if exits.next().is_some() {
+            return None;
+        }
+
+        let reversed = cfg.reversed();
+        let post_order = PostOrder::with_cfg(&reversed, exit);
+        Some(Self::with_cfg_and_post_order(&reversed, &post_order))
+    }
+
     /// Build a dominator tree from a control flow graph using Keith D. Cooper's
     /// "Simple, Fast Dominator Algorithm."
     fn compute_dominator_tree(&mut self, cfg: &ControlFlowGraph, post_order: &PostOrder) {
@@ -242,7 +306,7 @@ impl DominatorTree {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::cmp::Ordering;
 
     use crate::ssa_refactor::{
@@ -257,6 +321,41 @@ mod tests {
         ssa_builder::FunctionBuilder,
     };
 
+    /// Builds the function:
+    /// fn func {
+    ///   block0(cond: u1):
+    ///     jmpif cond, then: block1, else: block2
+    ///   block1():
+    ///     jmp block3()
+    ///   block2():
+    ///     jmp block3()
+    ///   block3():
+    ///     return ()
+    /// }
+    pub(crate) fn diamond() -> (Function, [BasicBlockId; 4]) {
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+        let block3_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        for block in [block1_id, block2_id] {
+            func.dfg[block].set_terminator(TerminatorInstruction::Jmp {
+                destination: block3_id,
+                arguments: vec![],
+            });
+        }
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+        (func, [block0_id, block1_id, block2_id, block3_id])
+    }
+
     #[test]
     fn empty() {
         let func_id = Id::test_new(0);
@@ -451,4 +550,74 @@ mod tests {
         assert!(dt.dominates(block2_id, block1_id));
         assert!(dt.dominates(block2_id, block2_id));
     }
+
+    #[test]
+    fn must_execute_before() {
+        // Follow the merge block of the diamond with:
+        //   block3():
+        //     jmp block4()
+        //   block4():
+        //     return ()
+        let (mut func, [block0_id, block1_id, block2_id, block3_id]) = diamond();
+        let block4_id = func.dfg.make_block();
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block4_id,
+            arguments: vec![],
+        });
+        func.dfg[block4_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let dt = DominatorTree::with_function(&func);
+
+        assert!(dt.must_execute_before(block0_id).is_empty());
+        assert_eq!(dt.must_execute_before(block1_id), vec![block0_id]);
+        assert_eq!(dt.must_execute_before(block2_id), vec![block0_id]);
+        // Neither arm of the branch is guaranteed to run before the merge block
+        assert_eq!(dt.must_execute_before(block3_id), vec![block0_id]);
+        assert_eq!(dt.must_execute_before(block4_id), vec![block0_id, block3_id]);
+    }
+
+    #[test]
+    fn are_siblings() {
+        let (func, [block0_id, block1_id, block2_id, block3_id]) = diamond();
+        let dt = DominatorTree::with_function(&func);
+
+        // Every block other than the entry is immediately dominated by the entry
+        assert!(dt.are_siblings(block1_id, block2_id));
+        assert!(dt.are_siblings(block2_id, block1_id));
+        assert!(dt.are_siblings(block1_id, block3_id));
+        assert!(!dt.are_siblings(block1_id, block1_id));
+
+        for block in [block0_id, block1_id, block2_id, block3_id] {
+            assert!(!dt.are_siblings(block0_id, block));
+            assert!(!dt.are_siblings(block, block0_id));
+        }
+    }
+
+    #[test]
+    fn lowest_common_dominator_of() {
+        let (func, [block0_id, block1_id, block2_id, block3_id]) = diamond();
+        let dt = DominatorTree::with_function(&func);
+
+        // A value used in both arms and the merge block can only be placed before the branch
+        assert_eq!(dt.lowest_common_dominator_of(&[block1_id, block2_id, block3_id]), block0_id);
+        assert_eq!(dt.lowest_common_dominator_of(&[block3_id, block1_id]), block0_id);
+        assert_eq!(dt.lowest_common_dominator_of(&[block3_id]), block3_id);
+        assert_eq!(dt.lowest_common_dominator_of(&[block0_id, block3_id]), block0_id);
+    }
+
+    #[test]
+    fn post_dominators() {
+        let (mut func, [block0_id, block1_id, block2_id, block3_id]) = diamond();
+
+        let mut pdt = DominatorTree::with_function_post_dom(&func).unwrap();
+        assert_eq!(pdt.immediate_dominator(block3_id), None);
+        for block in [block0_id, block1_id, block2_id] {
+            assert_eq!(pdt.immediate_dominator(block), Some(block3_id));
+        }
+        assert!(!pdt.dominates(block1_id, block0_id));
+
+        // Returning from block1 as well leaves no single exit to compute post-dominance against
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+        assert!(DominatorTree::with_function_post_dom(&func).is_none());
+    }
 }
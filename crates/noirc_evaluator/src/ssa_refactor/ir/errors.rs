@@ -0,0 +1,39 @@
+//! Errors raised when the IR is queried or modified in a way which breaks one of the compiler's
+//! own invariants. These are always bugs in the compiler rather than in the program being
+//! compiled, but returning them allows callers to attach context before reporting them.
+use thiserror::Error;
+
+use super::{basic_block::BasicBlockId, value::ValueId};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub(crate) enum InternalError {
+    /// A block was queried which is not present in the control flow graph.
+    #[error("ICE: Block {block} was not found within the cfg")]
+    BlockNotInCfg { block: BasicBlockId },
+
+    /// An edge was removed which is not present in the control flow graph.
+    #[error("ICE: Edge {from} -> {to} was not found within the cfg")]
+    EdgeNotInCfg { from: BasicBlockId, to: BasicBlockId },
+
+    /// A query which needs at least one block was made against an empty control flow graph.
+    #[error("ICE: The cfg contains no blocks")]
+    EmptyCfg,
+
+    /// A block was left without a terminator instruction.
+    #[error("ICE: {block} has no terminator")]
+    MissingTerminator { block: BasicBlockId },
+
+    /// A jmp passed a different number of arguments than its destination has parameters.
+    #[error("ICE: {block} passes {actual} arguments to {destination}, which expects {expected}")]
+    BlockArgumentMismatch {
+        block: BasicBlockId,
+        destination: BasicBlockId,
+        expected: usize,
+        actual: usize,
+    },
+
+    /// A value was used in a block which its definition does not dominate, or before its
+    /// definition within the same block.
+    #[error("ICE: {value} is used in {block} before it is defined")]
+    UseBeforeDefinition { value: ValueId, block: BasicBlockId },
+}
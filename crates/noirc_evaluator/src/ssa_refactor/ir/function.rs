@@ -1,6 +1,7 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
 use super::basic_block::BasicBlockId;
+use super::cfg::ControlFlowGraph;
 use super::dfg::DataFlowGraph;
 use super::instruction::TerminatorInstruction;
 use super::map::Id;
@@ -116,6 +117,94 @@ impl Function {
         }
         blocks
     }
+
+    /// Summarizes the shape of this function's reachable control flow.
+    ///
+    /// Unreachable blocks are only included in `block_count`, all other figures are
+    /// computed over the reachable blocks only.
+    pub(crate) fn stats(&self) -> FunctionStats {
+        let reachable_blocks = self.reachable_blocks();
+        let cfg = ControlFlowGraph::with_function(self);
+
+        let mut stats = FunctionStats {
+            block_count: self.dfg.basic_blocks_iter().len(),
+            reachable_block_count: reachable_blocks.len(),
+            ..FunctionStats::default()
+        };
+
+        for block in reachable_blocks {
+            let block_size = self.dfg[block].instructions().len();
+            stats.instruction_count += block_size;
+            stats.max_block_size = stats.max_block_size.max(block_size);
+
+            if cfg.predecessors(block).len() > 1 {
+                stats.join_point_count += 1;
+            }
+            if let Some(TerminatorInstruction::Return { .. }) = self.dfg[block].terminator() {
+                stats.return_block_count += 1;
+            }
+        }
+        stats
+    }
+
+    /// Maps each block size to the number of reachable blocks of that size, where a block's
+    /// size is its number of instructions excluding its terminator, as in `Function::stats`.
+    pub(crate) fn block_size_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for block in self.reachable_blocks() {
+            *histogram.entry(self.dfg[block].instructions().len()).or_default() += 1;
+        }
+        histogram
+    }
+
+    /// Renders this function's reachable blocks as a Graphviz digraph, with each block's
+    /// parameters, instructions, and terminator listed in its node.
+    pub(crate) fn to_dot(&self) -> String {
+        super::printer::function_to_dot(self)
+    }
+}
+
+/// A summary of the shape of a function, as returned by `Function::stats`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FunctionStats {
+    /// Total number of blocks in the function, including unreachable ones.
+    pub(crate) block_count: usize,
+    /// Number of blocks reachable from the entry block.
+    pub(crate) reachable_block_count: usize,
+    /// Number of instructions across all reachable blocks, excluding terminators.
+    pub(crate) instruction_count: usize,
+    /// Number of instructions in the largest reachable block.
+    pub(crate) max_block_size: usize,
+    /// Number of reachable blocks with more than one predecessor.
+    pub(crate) join_point_count: usize,
+    /// Number of reachable blocks terminated by a return instruction.
+    pub(crate) return_block_count: usize,
+}
+
+impl FunctionStats {
+    /// The mean number of instructions per reachable block.
+    pub(crate) fn average_block_size(&self) -> f64 {
+        if self.reachable_block_count == 0 {
+            0.0
+        } else {
+            self.instruction_count as f64 / self.reachable_block_count as f64
+        }
+    }
+}
+
+impl std::fmt::Display for FunctionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "blocks: {} ({} reachable)", self.block_count, self.reachable_block_count)?;
+        writeln!(f, "instructions: {}", self.instruction_count)?;
+        writeln!(
+            f,
+            "block size: max {}, average {:.2}",
+            self.max_block_size,
+            self.average_block_size()
+        )?;
+        writeln!(f, "join points: {}", self.join_point_count)?;
+        write!(f, "return blocks: {}", self.return_block_count)
+    }
 }
 
 impl std::fmt::Display for RuntimeType {
@@ -152,3 +241,127 @@ fn sign_smoke() {
     signature.params.push(Type::Numeric(super::types::NumericType::NativeField));
     signature.returns.push(Type::Numeric(super::types::NumericType::Unsigned { bit_size: 32 }));
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa_refactor::ir::{
+        instruction::{Instruction, TerminatorInstruction},
+        map::Id,
+        types::Type,
+    };
+
+    use super::Function;
+
+    /// Builds the function:
+    /// fn func {
+    ///   block0(cond: u1):
+    ///     jmpif cond, then: block2, else: block1
+    ///   block1():
+    ///     jmpif cond, then: block1, else: block2
+    ///   block2():
+    ///     return ()
+    /// }
+    fn jumps() -> Function {
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block2_id,
+            else_destination: block1_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: cond,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+        func
+    }
+
+    #[test]
+    fn stats_of_jumps() {
+        // Add an unreachable block3 to the jumps function:
+        //   block3():
+        //     return ()
+        let mut func = jumps();
+        let block3_id = func.dfg.make_block();
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let stats = func.stats();
+        assert_eq!(stats.block_count, 4);
+        assert_eq!(stats.reachable_block_count, 3);
+        assert_eq!(stats.instruction_count, 0);
+        assert_eq!(stats.max_block_size, 0);
+        assert_eq!(stats.average_block_size(), 0.0);
+        // block1 is reached from block0 and itself, block2 from block0 and block1
+        assert_eq!(stats.join_point_count, 2);
+        // The unreachable block3 is not counted
+        assert_eq!(stats.return_block_count, 1);
+    }
+
+    #[test]
+    fn block_size_histogram() {
+        // fn func {
+        //   block0(cond: u1):
+        //     v1 = not cond
+        //     jmpif v1, then: block1, else: block2
+        //   block1():
+        //     v2 = not cond
+        //     v3 = not cond
+        //     v4 = not cond
+        //     jmp block2()
+        //   block2():
+        //     return ()
+        // }
+        let func_id = Id::test_new(0);
+        let mut func = Function::new("func".into(), func_id);
+        let block0_id = func.entry_block();
+        let cond = func.dfg.add_block_parameter(block0_id, Type::unsigned(1));
+        let block1_id = func.dfg.make_block();
+        let block2_id = func.dfg.make_block();
+
+        let not = func.dfg.make_instruction(Instruction::Not(cond), None);
+        func.dfg[block0_id].insert_instruction(not);
+        let v1 = func.dfg.instruction_results(not)[0];
+        for _ in 0..3 {
+            let not = func.dfg.make_instruction(Instruction::Not(cond), None);
+            func.dfg[block1_id].insert_instruction(not);
+        }
+
+        func.dfg[block0_id].set_terminator(TerminatorInstruction::JmpIf {
+            condition: v1,
+            then_destination: block1_id,
+            else_destination: block2_id,
+        });
+        func.dfg[block1_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block2_id,
+            arguments: vec![],
+        });
+        func.dfg[block2_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        // An unreachable block is not counted
+        let block3_id = func.dfg.make_block();
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let histogram = func.block_size_histogram();
+        assert_eq!(histogram.into_iter().collect::<Vec<_>>(), vec![(0, 1), (1, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn dot_of_jumps() {
+        let dot = jumps().to_dot();
+
+        assert!(dot.starts_with("digraph \"func f0\" {"));
+        assert!(dot.contains("b0 [label=\"{b0(v0: u1):||jmpif v0 then: b2, else: b1\\l}\"];"));
+        assert!(dot.contains("b1 [label=\"{b1():||jmpif v0 then: b1, else: b2\\l}\"];"));
+        assert!(dot.contains("b2 [label=\"{b2():||return\\l}\"];"));
+        assert!(dot.contains("b0 -> b2 [label=\"then\"];"));
+        assert!(dot.contains("b1 -> b1 [label=\"then\"];"));
+        assert!(dot.contains("b1 -> b2 [label=\"else\"];"));
+    }
+}
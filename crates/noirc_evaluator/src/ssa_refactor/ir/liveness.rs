@@ -0,0 +1,359 @@
+//! Liveness analysis of the values within a function, represented as the sets of values live on
+//! entry to and on exit from each reachable block.
+//!
+//! A value is live at a point if it may be used at some later point in the function without
+//! being redefined in between. Since the IR is in SSA form, each value is defined exactly once:
+//! either as a block parameter or as the result of an instruction. Values passed as jmp
+//! arguments are used by the jumping block, so they are not live into the destination block
+//! under their original ids.
+//!
+//! Those arguments are also tracked for each edge, so that the values live along a particular
+//! edge can be queried with `Liveness::live_on_edge`.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    basic_block::BasicBlockId,
+    cfg::ControlFlowGraph,
+    dfg::DataFlowGraph,
+    function::Function,
+    instruction::TerminatorInstruction,
+    post_order::PostOrder,
+    value::{Value, ValueId},
+};
+
+/// The live-in and live-out sets of each reachable block within a function.
+pub(crate) struct Liveness {
+    live_in: HashMap<BasicBlockId, HashSet<ValueId>>,
+    live_out: HashMap<BasicBlockId, HashSet<ValueId>>,
+    /// The values each block passes as arguments to each of its successors.
+    edge_arguments: HashMap<BasicBlockId, HashMap<BasicBlockId, HashSet<ValueId>>>,
+}
+
+impl Liveness {
+    /// Allocate and compute the liveness of each reachable block in `func`.
+    ///
+    /// This computes the control flow graph internally and then discards it. If it should be
+    /// retained for reuse it is better to instead pre-compute it and build the liveness with
+    /// `Liveness::with_cfg`.
+    pub(crate) fn with_function(func: &Function) -> Self {
+        let cfg = ControlFlowGraph::with_function(func);
+        Self::with_cfg(func, &cfg)
+    }
+
+    /// Allocate and compute the liveness of each reachable block in `func` from its
+    /// pre-computed control flow graph.
+    pub(crate) fn with_cfg(func: &Function, cfg: &ControlFlowGraph) -> Self {
+        let mut liveness = Liveness {
+            live_in: HashMap::new(),
+            live_out: HashMap::new(),
+            edge_arguments: HashMap::new(),
+        };
+
+        // Liveness flows backwards so start from the blocks nearest the exit. The worklist is
+        // popped from the back, hence the reversal.
+        let post_order = PostOrder::with_function(func);
+        let worklist = post_order.as_slice().iter().rev().copied().collect();
+        liveness.solve(func, cfg, worklist);
+        liveness
+    }
+
+    /// Returns the values live on entry to `block`.
+    pub(crate) fn live_in(&self, block: BasicBlockId) -> &HashSet<ValueId> {
+        self.live_in
+            .get(&block)
+            .expect("ICE: Attempted to query liveness of block not found within liveness.")
+    }
+
+    /// Returns the values live on exit from `block`.
+    pub(crate) fn live_out(&self, block: BasicBlockId) -> &HashSet<ValueId> {
+        self.live_out
+            .get(&block)
+            .expect("ICE: Attempted to query liveness of block not found within liveness.")
+    }
+
+    /// Returns the values live along the edge from `from` to `to`: those live on entry to `to`,
+    /// along with the values `from` passes as arguments to `to`'s parameters. The arguments are
+    /// not live into `to` under their own ids, but must still be available up to the jump.
+    pub(crate) fn live_on_edge(&self, from: BasicBlockId, to: BasicBlockId) -> HashSet<ValueId> {
+        let arguments = self
+            .edge_arguments
+            .get(&from)
+            .and_then(|edges| edges.get(&to))
+            .expect("ICE: Attempted to query liveness of edge not found within liveness.");
+        self.live_in(to).union(arguments).copied().collect()
+    }
+
+    /// Recompute liveness after modifying the instructions within a specific block.
+    ///
+    /// Only blocks from which `block` can be reached may have had their liveness affected by
+    /// the edit, so only those are recomputed. They are cleared and solved again together so
+    /// that the result matches a full recomputation, even if uses were removed within a loop.
+    ///
+    /// This expects `cfg` to be up to date, see `ControlFlowGraph::recompute_block`.
+    pub(crate) fn recompute_block(
+        &mut self,
+        func: &Function,
+        cfg: &ControlFlowGraph,
+        block: BasicBlockId,
+    ) {
+        let mut affected = HashSet::new();
+        let mut stack = vec![block];
+        while let Some(block) = stack.pop() {
+            if affected.insert(block) {
+                stack.extend(cfg.predecessors(block));
+            }
+        }
+
+        for block in &affected {
+            self.live_in.remove(block);
+            self.live_out.remove(block);
+            self.edge_arguments.remove(block);
+        }
+        self.solve(func, cfg, affected.into_iter().collect());
+    }
+
+    /// Runs the liveness transfer function on each block in the worklist, re-adding a block's
+    /// predecessors whenever its live-in set changes, until a fixed point is reached.
+    ///
+    /// Blocks not yet in `self` are treated as having nothing live.
+    fn solve(&mut self, func: &Function, cfg: &ControlFlowGraph, mut worklist: Vec<BasicBlockId>) {
+        let mut in_worklist: HashSet<_> = worklist.iter().copied().collect();
+
+        while let Some(block) = worklist.pop() {
+            in_worklist.remove(&block);
+
+            let mut live_out = HashSet::new();
+            for successor in cfg.successors(block) {
+                if let Some(successor_live_in) = self.live_in.get(&successor) {
+                    live_out.extend(successor_live_in.iter().copied());
+                }
+            }
+
+            let live_in = block_live_in(func, block, &live_out);
+            let changed = self.live_in.get(&block) != Some(&live_in);
+            self.live_in.insert(block, live_in);
+            self.live_out.insert(block, live_out);
+            self.edge_arguments.insert(block, block_edge_arguments(func, cfg, block));
+
+            if changed {
+                for predecessor in cfg.predecessors(block) {
+                    if in_worklist.insert(predecessor) {
+                        worklist.push(predecessor);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Computes the values live on entry to `block` given the values live on exit from it: those
+/// used within the block and those live on exit, except for any the block itself defines.
+fn block_live_in(
+    func: &Function,
+    block: BasicBlockId,
+    live_out: &HashSet<ValueId>,
+) -> HashSet<ValueId> {
+    let dfg = &func.dfg;
+    let block = &dfg[block];
+
+    let mut live = live_out.clone();
+    block.unwrap_terminator().for_each_value(|value| insert_used_values(dfg, value, &mut live));
+
+    // Step backwards through the block so that each instruction's results are removed from the
+    // set before the values the instruction itself uses are added.
+    for instruction in block.instructions().iter().rev() {
+        for result in dfg.instruction_results(*instruction) {
+            live.remove(result);
+        }
+        dfg[*instruction].for_each_value(|value| insert_used_values(dfg, value, &mut live));
+    }
+
+    for parameter in block.parameters() {
+        live.remove(parameter);
+    }
+    live
+}
+
+/// Collects the values `block` passes as arguments to each of its successors. Successors which
+/// take no arguments from `block` map to an empty set.
+fn block_edge_arguments(
+    func: &Function,
+    cfg: &ControlFlowGraph,
+    block: BasicBlockId,
+) -> HashMap<BasicBlockId, HashSet<ValueId>> {
+    let dfg = &func.dfg;
+    let mut edges: HashMap<_, _> =
+        cfg.successors(block).map(|successor| (successor, HashSet::new())).collect();
+
+    if let Some(TerminatorInstruction::Jmp { destination, arguments }) = dfg[block].terminator() {
+        let passed = edges.entry(*destination).or_default();
+        for argument in arguments {
+            insert_used_values(dfg, *argument, passed);
+        }
+    }
+    edges
+}
+
+/// Inspects a value recursively (as it could be an array) and inserts each comprised
+/// instruction result or block parameter into `live`. Constants, functions, and intrinsics
+/// are never considered live as they are not defined within any block.
+fn insert_used_values(dfg: &DataFlowGraph, value_id: ValueId, live: &mut HashSet<ValueId>) {
+    let value_id = dfg.resolve(value_id);
+    match &dfg[value_id] {
+        Value::Instruction { .. } | Value::Param { .. } => {
+            live.insert(value_id);
+        }
+        Value::Array { array, .. } => {
+            for element in array {
+                insert_used_values(dfg, *element, live);
+            }
+        }
+        Value::NumericConstant { .. }
+        | Value::Function(_)
+        | Value::Intrinsic(_)
+        | Value::ForeignFunction(_) => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::ssa_refactor::{
+        ir::{
+            cfg::ControlFlowGraph,
+            function::RuntimeType,
+            instruction::{Binary, BinaryOp, Instruction},
+            map::Id,
+            types::Type,
+        },
+        ssa_builder::FunctionBuilder,
+    };
+
+    use super::Liveness;
+
+    #[test]
+    fn recompute_block_matches_full_recomputation() {
+        // fn main {
+        //   b0(v0: Field, v1: Field):
+        //     v2 = add v0, Field 1
+        //     jmp b1(v2)
+        //   b1(v3: Field):
+        //     v4 = lt v3, Field 10
+        //     jmpif v4 then: b3, else: b2
+        //   b3():
+        //     jmp b1(v3)
+        //   b2():
+        //     return v3
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+        let v1 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let v3 = builder.add_block_parameter(b1, Type::field());
+
+        let one = builder.field_constant(1u128);
+        let ten = builder.field_constant(10u128);
+
+        let v2 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v2]);
+
+        builder.switch_to_block(b1);
+        let v4 = builder.insert_binary(v3, BinaryOp::Lt, ten);
+        builder.terminate_with_jmpif(v4, b3, b2);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_jmp(b1, vec![v3]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![v3]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        let b0 = main.entry_block();
+        let cfg = ControlFlowGraph::with_function(main);
+        let mut liveness = Liveness::with_cfg(main, &cfg);
+
+        assert!(liveness.live_in(b0).is_empty());
+        assert!(liveness.live_in(b1).is_empty());
+        assert_eq!(liveness.live_in(b2).len(), 1);
+        assert!(liveness.live_in(b2).contains(&v3));
+
+        // Edit b2 to also use v1, which must then be live throughout the loop:
+        //   b2():
+        //     v5 = add v3, v1
+        //     return v3
+        let instruction = Instruction::Binary(Binary { lhs: v3, rhs: v1, operator: BinaryOp::Add });
+        let instruction_id = main.dfg.make_instruction(instruction, None);
+        main.dfg[b2].insert_instruction(instruction_id);
+
+        liveness.recompute_block(main, &cfg, b2);
+        let full = Liveness::with_cfg(main, &cfg);
+
+        for block in [b0, b1, b2, b3] {
+            assert_eq!(liveness.live_in(block), full.live_in(block));
+            assert_eq!(liveness.live_out(block), full.live_out(block));
+        }
+        assert!(liveness.live_in(b1).contains(&v1));
+        assert!(liveness.live_out(b0).contains(&v1));
+
+        // Removing the use again should shrink liveness back, even around the loop
+        main.dfg[b2].remove_instruction(instruction_id);
+        liveness.recompute_block(main, &cfg, b2);
+        assert!(liveness.live_in(b1).is_empty());
+        assert!(liveness.live_out(b0).is_empty());
+    }
+
+    #[test]
+    fn live_on_edge() {
+        // fn main {
+        //   b0(v0: u1, v1: Field, v2: Field):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     v3 = add v1, v2
+        //     jmp b3(v3)
+        //   b2():
+        //     jmp b3(v1)
+        //   b3(v4: Field):
+        //     v5 = add v4, v2
+        //     return v5
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::field());
+        let v2 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let v4 = builder.add_block_parameter(b3, Type::field());
+
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        let v3 = builder.insert_binary(v1, BinaryOp::Add, v2);
+        builder.terminate_with_jmp(b3, vec![v3]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_jmp(b3, vec![v1]);
+
+        builder.switch_to_block(b3);
+        let v5 = builder.insert_binary(v4, BinaryOp::Add, v2);
+        builder.terminate_with_return(vec![v5]);
+
+        let ssa = builder.finish();
+        let main = ssa.main();
+        let liveness = Liveness::with_function(main);
+
+        // Only v2 is live into b3 itself, but each arm must also hold the argument it passes
+        assert_eq!(liveness.live_in(b3), &HashSet::from([v2]));
+        assert_eq!(liveness.live_on_edge(b1, b3), HashSet::from([v2, v3]));
+        assert_eq!(liveness.live_on_edge(b2, b3), HashSet::from([v1, v2]));
+    }
+}
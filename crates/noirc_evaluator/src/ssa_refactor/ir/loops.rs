@@ -0,0 +1,352 @@
+//! Natural loops within a function's control flow graph.
+//!
+//! A natural loop is identified by a back-edge `n -> d`, where the header block `d` dominates
+//! `n`. The loop consists of `d` along with every block that can reach `n` without passing
+//! through `d`.
+
+use std::collections::HashSet;
+
+use super::{
+    basic_block::BasicBlockId, cfg::ControlFlowGraph, function::Function,
+    instruction::TerminatorInstruction,
+};
+
+/// A single natural loop, identified by its header block and the back-edge into it.
+pub(crate) struct NaturalLoop {
+    /// The header block of a loop is the block which dominates all the
+    /// other blocks in the loop.
+    header: BasicBlockId,
+
+    /// The start of the back_edge n -> d is the block n at the end of
+    /// the loop that jumps back to the header block d which restarts the loop.
+    latch: BasicBlockId,
+
+    /// All the blocks contained within the loop, including `header` and `latch`.
+    blocks: HashSet<BasicBlockId>,
+}
+
+impl NaturalLoop {
+    /// Collect each block that is in the loop starting in the given header block.
+    /// Expects latch -> header to be a back-edge within `cfg`.
+    pub(crate) fn with_back_edge(
+        header: BasicBlockId,
+        latch: BasicBlockId,
+        cfg: &ControlFlowGraph,
+    ) -> Self {
+        let mut blocks = HashSet::new();
+        blocks.insert(header);
+
+        let mut insert = |block, stack: &mut Vec<BasicBlockId>| {
+            if !blocks.contains(&block) {
+                blocks.insert(block);
+                stack.push(block);
+            }
+        };
+
+        // Starting from the back edge of the loop, each predecessor of this block until
+        // the header is within the loop.
+        let mut stack = vec![];
+        insert(latch, &mut stack);
+
+        while let Some(block) = stack.pop() {
+            for predecessor in cfg.predecessors(block) {
+                insert(predecessor, &mut stack);
+            }
+        }
+
+        Self { header, latch, blocks }
+    }
+
+    /// Returns the header block of this loop, the single entry point into the loop.
+    pub(crate) fn header(&self) -> BasicBlockId {
+        self.header
+    }
+
+    /// Returns the latch of this loop: the block which jumps back to the header.
+    pub(crate) fn latch(&self) -> BasicBlockId {
+        self.latch
+    }
+
+    /// Returns every block within this loop, including the header and latch.
+    pub(crate) fn blocks(&self) -> &HashSet<BasicBlockId> {
+        &self.blocks
+    }
+
+    /// Returns true if the given block is part of this loop.
+    pub(crate) fn contains(&self, block: BasicBlockId) -> bool {
+        self.blocks.contains(&block)
+    }
+
+    /// The loop pre-header is the block that comes before the loop begins. Generally a header
+    /// block is expected to have 2 predecessors: the pre-header and the latch which jumps back
+    /// to the beginning.
+    ///
+    /// Returns None if the header does not have exactly one predecessor outside of the loop.
+    pub(crate) fn preheader(&self, cfg: &ControlFlowGraph) -> Option<BasicBlockId> {
+        let mut outside_predecessors =
+            cfg.predecessors(self.header).filter(|predecessor| !self.contains(*predecessor));
+
+        let preheader = outside_predecessors.next()?;
+        outside_predecessors.next().is_none().then_some(preheader)
+    }
+}
+
+impl Function {
+    /// Returns the pre-header of the given loop, first inserting one if the loop's header does
+    /// not have a single predecessor outside of the loop.
+    ///
+    /// An inserted pre-header takes the same parameters as the header and forwards them to it
+    /// with a jmp. Each predecessor of the header from outside the loop is rewired to jump to
+    /// the pre-header instead, which leaves the latch as the only other predecessor of the
+    /// header. `cfg` is updated to reflect the new edges.
+    ///
+    /// A header entered from several blocks outside the loop has more predecessors than
+    /// `ControlFlowGraph::with_function` allows, so such a function's `cfg` must be built with
+    /// `ControlFlowGraph::with_function_bounds`.
+    pub(crate) fn ensure_preheader(
+        &mut self,
+        loop_: &NaturalLoop,
+        cfg: &mut ControlFlowGraph,
+    ) -> BasicBlockId {
+        if let Some(preheader) = loop_.preheader(cfg) {
+            return preheader;
+        }
+
+        let header = loop_.header();
+        let outside_predecessors: Vec<_> =
+            cfg.predecessors(header).filter(|predecessor| !loop_.contains(*predecessor)).collect();
+        assert!(
+            !outside_predecessors.is_empty(),
+            "ICE: Cannot insert a pre-header for a loop whose header is unreachable from outside it"
+        );
+
+        let preheader = self.dfg.make_block_with_parameters_from_block(header);
+        let arguments = self.dfg.block_parameters(preheader).to_vec();
+        let jmp = TerminatorInstruction::Jmp { destination: header, arguments };
+        self.dfg.set_block_terminator(preheader, jmp);
+
+        for predecessor in outside_predecessors {
+            self.dfg[predecessor].unwrap_terminator_mut().mutate_blocks(|target| {
+                if target == header {
+                    preheader
+                } else {
+                    target
+                }
+            });
+            cfg.recompute_block(self, predecessor);
+        }
+        cfg.recompute_block(self, preheader);
+
+        preheader
+    }
+
+    /// Returns the reachable blocks which can only ever jump back to themselves, sorted by id.
+    ///
+    /// Such a block never reaches a return once entered, which almost always indicates
+    /// malformed lowering rather than an intentional loop. Loops spanning several blocks are
+    /// not reported.
+    pub(crate) fn find_trivial_infinite_loops(&self, cfg: &ControlFlowGraph) -> Vec<BasicBlockId> {
+        let mut blocks: Vec<_> = self
+            .reachable_blocks()
+            .into_iter()
+            .filter(|block| {
+                let mut successors = cfg.successors(*block);
+                successors.len() == 1 && successors.next() == Some(*block)
+            })
+            .collect();
+        blocks.sort();
+        blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa_refactor::{
+        ir::{
+            cfg::ControlFlowGraph,
+            function::RuntimeType,
+            instruction::{BinaryOp, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
+        ssa_builder::FunctionBuilder,
+    };
+
+    use super::NaturalLoop;
+
+    #[test]
+    fn two_block_loop() {
+        // fn main {
+        //   b0():
+        //     jmp b1(Field 0)
+        //   b1(v0: Field):
+        //     v1 = lt v0, Field 4
+        //     jmpif v1 then: b2, else: b3
+        //   b2():
+        //     v2 = add v0, Field 1
+        //     jmp b1(v2)
+        //   b3():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let v0 = builder.add_block_parameter(b1, Type::field());
+
+        let zero = builder.field_constant(0u128);
+        let one = builder.field_constant(1u128);
+        let four = builder.field_constant(4u128);
+        builder.terminate_with_jmp(b1, vec![zero]);
+
+        builder.switch_to_block(b1);
+        let v1 = builder.insert_binary(v0, BinaryOp::Lt, four);
+        builder.terminate_with_jmpif(v1, b2, b3);
+
+        builder.switch_to_block(b2);
+        let v2 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v2]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish();
+        let main = ssa.main();
+        let b0 = main.entry_block();
+        let cfg = ControlFlowGraph::with_function(main);
+
+        let loop_ = NaturalLoop::with_back_edge(b1, b2, &cfg);
+        assert_eq!(loop_.header(), b1);
+        assert_eq!(loop_.latch(), b2);
+        assert_eq!(loop_.blocks().len(), 2);
+        assert!(loop_.contains(b1));
+        assert!(loop_.contains(b2));
+        assert!(!loop_.contains(b0));
+        assert!(!loop_.contains(b3));
+        assert_eq!(loop_.preheader(&cfg), Some(b0));
+    }
+
+    #[test]
+    fn insert_preheader() {
+        // fn main {
+        //   b0(v0: u1):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     jmp b3(Field 0)
+        //   b2():
+        //     jmp b3(Field 1)
+        //   b3(v1: Field):
+        //     v2 = lt v1, Field 4
+        //     jmpif v2 then: b4, else: b5
+        //   b4():
+        //     v3 = add v1, Field 1
+        //     jmp b3(v3)
+        //   b5():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let b4 = builder.insert_block();
+        let b5 = builder.insert_block();
+        let v1 = builder.add_block_parameter(b3, Type::field());
+
+        let zero = builder.field_constant(0u128);
+        let one = builder.field_constant(1u128);
+        let four = builder.field_constant(4u128);
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        builder.terminate_with_jmp(b3, vec![zero]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_jmp(b3, vec![one]);
+
+        builder.switch_to_block(b3);
+        let v2 = builder.insert_binary(v1, BinaryOp::Lt, four);
+        builder.terminate_with_jmpif(v2, b4, b5);
+
+        builder.switch_to_block(b4);
+        let v3 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b3, vec![v3]);
+
+        builder.switch_to_block(b5);
+        builder.terminate_with_return(vec![]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        // b3 is entered from b1, b2 and its latch b4
+        let mut cfg = ControlFlowGraph::with_function_bounds(main, 2, 3);
+
+        let loop_ = NaturalLoop::with_back_edge(b3, b4, &cfg);
+        assert_eq!(loop_.preheader(&cfg), None);
+
+        let preheader = main.ensure_preheader(&loop_, &mut cfg);
+        assert!(![b1, b2, b3, b4, b5].contains(&preheader));
+        assert_eq!(loop_.preheader(&cfg), Some(preheader));
+
+        let mut header_predecessors: Vec<_> = cfg.predecessors(b3).collect();
+        header_predecessors.sort();
+        let mut expected = vec![b4, preheader];
+        expected.sort();
+        assert_eq!(header_predecessors, expected);
+
+        for block in [b1, b2] {
+            match main.dfg[block].terminator() {
+                Some(TerminatorInstruction::Jmp { destination, arguments }) => {
+                    assert_eq!(*destination, preheader);
+                    assert_eq!(arguments.len(), 1);
+                }
+                other => panic!("Expected a jmp to the pre-header, got {other:?}"),
+            }
+        }
+
+        let preheader_parameters = main.dfg[preheader].parameters().to_vec();
+        assert_eq!(preheader_parameters.len(), 1);
+        assert_eq!(
+            main.dfg[preheader].terminator(),
+            Some(&TerminatorInstruction::Jmp { destination: b3, arguments: preheader_parameters })
+        );
+
+        // A loop which already has a pre-header is left unchanged
+        assert_eq!(main.ensure_preheader(&loop_, &mut cfg), preheader);
+    }
+
+    #[test]
+    fn trivial_infinite_loop() {
+        // fn main {
+        //   b0(v0: u1):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     jmp b1()
+        //   b2():
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        builder.terminate_with_jmp(b1, vec![]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish();
+        let main = ssa.main();
+        let cfg = ControlFlowGraph::with_function(main);
+
+        assert_eq!(main.find_trivial_infinite_loops(&cfg), vec![b1]);
+    }
+}
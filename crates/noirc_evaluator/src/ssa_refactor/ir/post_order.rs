@@ -5,7 +5,9 @@
 
 use std::collections::HashSet;
 
-use crate::ssa_refactor::ir::{basic_block::BasicBlockId, function::Function};
+use crate::ssa_refactor::ir::{
+    basic_block::BasicBlockId, cfg::ControlFlowGraph, function::Function,
+};
 
 /// Depth-first traversal stack state marker for computing the cfg post-order.
 enum Visit {
@@ -27,6 +29,32 @@ impl PostOrder {
         PostOrder(Self::compute_post_order(func))
     }
 
+    /// Allocate and compute the post-order of the blocks reachable from `entry` along the
+    /// successors within `cfg`. Unlike `PostOrder::with_function` this may start from any block,
+    /// such as the exit block of a reversed cfg.
+    pub(crate) fn with_cfg(cfg: &ControlFlowGraph, entry: BasicBlockId) -> Self {
+        let mut stack = vec![(Visit::First, entry)];
+        let mut visited: HashSet<BasicBlockId> = HashSet::new();
+        let mut post_order: Vec<BasicBlockId> = Vec::new();
+
+        while let Some((visit, block_id)) = stack.pop() {
+            match visit {
+                Visit::First => {
+                    if visited.insert(block_id) {
+                        stack.push((Visit::Last, block_id));
+                        for successor_id in cfg.successors_sorted(block_id).into_iter().rev() {
+                            if !visited.contains(&successor_id) {
+                                stack.push((Visit::First, successor_id));
+                            }
+                        }
+                    }
+                }
+                Visit::Last => post_order.push(block_id),
+            }
+        }
+        PostOrder(post_order)
+    }
+
     // Computes the post-order of the function by doing a depth-first traversal of the
     // function's entry block's previously unvisited children. Each block is sequenced according
     // to when the traversal exits it.
@@ -1,7 +1,7 @@
 //! This file is for pretty-printing the SSA IR in a human-readable form for debugging.
 use std::{
     collections::HashSet,
-    fmt::{Formatter, Result},
+    fmt::{Display, Formatter, Result},
 };
 
 use iter_extended::vecmap;
@@ -173,3 +173,71 @@ pub(crate) fn display_instruction(
         }
     }
 }
+
+/// Render the function as a Graphviz digraph. Each reachable block becomes a record node listing
+/// its parameters, instructions, and terminator, with an edge to each of its successors.
+pub(crate) fn function_to_dot(function: &Function) -> String {
+    let mut blocks: Vec<_> = function.reachable_blocks().into_iter().collect();
+    blocks.sort();
+
+    let mut dot = format!("digraph \"{} {}\" {{\n", escape_dot(function.name()), function.id());
+    dot.push_str("  node [shape=record, fontname=monospace];\n");
+
+    for block_id in &blocks {
+        let block = &function.dfg[*block_id];
+        let parameters = value_list_with_types(function, block.parameters());
+
+        let mut instructions = String::new();
+        for instruction in block.instructions() {
+            let line = DisplayWith(|f| display_instruction(function, *instruction, f)).to_string();
+            instructions.push_str(&escape_dot(line.trim()));
+            instructions.push_str("\\l");
+        }
+
+        let terminator =
+            DisplayWith(|f| display_terminator(function, block.terminator(), f)).to_string();
+
+        dot.push_str(&format!(
+            "  {block_id} [label=\"{{{}|{instructions}|{}\\l}}\"];\n",
+            escape_dot(&format!("{block_id}({parameters}):")),
+            escape_dot(terminator.trim()),
+        ));
+    }
+
+    for block_id in &blocks {
+        match function.dfg[*block_id].terminator() {
+            Some(TerminatorInstruction::JmpIf { then_destination, else_destination, .. }) => {
+                dot.push_str(&format!("  {block_id} -> {then_destination} [label=\"then\"];\n"));
+                dot.push_str(&format!("  {block_id} -> {else_destination} [label=\"else\"];\n"));
+            }
+            Some(TerminatorInstruction::Jmp { destination, .. }) => {
+                dot.push_str(&format!("  {block_id} -> {destination};\n"));
+            }
+            Some(TerminatorInstruction::Return { .. }) | None => (),
+        }
+    }
+
+    dot.push('}');
+    dot
+}
+
+/// Escape characters which have a special meaning within a Graphviz record label.
+fn escape_dot(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        if matches!(char, '{' | '}' | '|' | '<' | '>' | '"' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(char);
+    }
+    escaped
+}
+
+/// Adapts one of the `display_*` helpers above into a value implementing Display.
+struct DisplayWith<F: Fn(&mut Formatter) -> Result>(F);
+
+impl<F: Fn(&mut Formatter) -> Result> Display for DisplayWith<F> {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        (self.0)(f)
+    }
+}
@@ -0,0 +1,80 @@
+//! Single-entry single-exit (SESE) regions of a function's control flow graph.
+//!
+//! A region is bounded by an entry block and an exit block, where the entry dominates the exit
+//! and the exit post-dominates the entry. The region then contains every block which the entry
+//! dominates and the exit post-dominates, so control can only enter it through the entry block
+//! and only leave it through the exit block. This allows the region to be optimized in isolation.
+use std::collections::HashSet;
+
+use super::{basic_block::BasicBlockId, dom::DominatorTree};
+
+/// Returns the blocks of the SESE region bounded by `entry` and `exit`, including both of them,
+/// given the function's dominator tree `dom` and post-dominator tree `pdom`.
+///
+/// Returns `None` if `entry` and `exit` do not form a valid pair: `entry` must dominate `exit`,
+/// `exit` must post-dominate `entry`, and both must be reachable within each tree.
+pub(crate) fn find_sese_region(
+    dom: &DominatorTree,
+    pdom: &DominatorTree,
+    entry: BasicBlockId,
+    exit: BasicBlockId,
+) -> Option<HashSet<BasicBlockId>> {
+    let is_reachable = |block| dom.is_reachable(block) && pdom.is_reachable(block);
+    if !is_reachable(entry)
+        || !is_reachable(exit)
+        || !dom.dominates_helper(entry, exit)
+        || !pdom.dominates_helper(exit, entry)
+    {
+        return None;
+    }
+
+    let region = dom
+        .blocks()
+        .filter(|block| {
+            pdom.is_reachable(*block)
+                && dom.dominates_helper(entry, *block)
+                && pdom.dominates_helper(exit, *block)
+        })
+        .collect();
+    Some(region)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::ssa_refactor::ir::{
+        dom::{tests::diamond, DominatorTree},
+        instruction::TerminatorInstruction,
+    };
+
+    use super::find_sese_region;
+
+    #[test]
+    fn diamond_region() {
+        // Follow the merge block of the diamond with:
+        //   block3():
+        //     jmp block4()
+        //   block4():
+        //     return ()
+        let (mut func, [block0_id, block1_id, block2_id, block3_id]) = diamond();
+        let block4_id = func.dfg.make_block();
+        func.dfg[block3_id].set_terminator(TerminatorInstruction::Jmp {
+            destination: block4_id,
+            arguments: vec![],
+        });
+        func.dfg[block4_id].set_terminator(TerminatorInstruction::Return { return_values: vec![] });
+
+        let dom = DominatorTree::with_function(&func);
+        let pdom = DominatorTree::with_function_post_dom(&func).unwrap();
+
+        // The branch and merge blocks bound both arms, but not the block after the merge
+        let region = find_sese_region(&dom, &pdom, block0_id, block3_id);
+        assert_eq!(region, Some(HashSet::from([block0_id, block1_id, block2_id, block3_id])));
+
+        // block1 does not dominate the merge block, as it can be bypassed through block2
+        assert_eq!(find_sese_region(&dom, &pdom, block1_id, block3_id), None);
+        // block2 does not post-dominate block0, which can reach the exit through block1
+        assert_eq!(find_sese_region(&dom, &pdom, block0_id, block2_id), None);
+    }
+}
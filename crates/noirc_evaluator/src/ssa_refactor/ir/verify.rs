@@ -0,0 +1,280 @@
+//! Whole-function checks that a function is in valid SSA form.
+//!
+//! These are not run as part of compilation but are useful as a sanity check when writing or
+//! debugging an optimization pass.
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    basic_block::BasicBlockId,
+    cfg::ControlFlowGraph,
+    dfg::DataFlowGraph,
+    dom::DominatorTree,
+    errors::InternalError,
+    function::Function,
+    instruction::{InstructionId, TerminatorInstruction},
+    post_order::PostOrder,
+    value::{Value, ValueId},
+};
+
+impl Function {
+    /// Checks that the function is ready to be handed to a backend, returning every problem
+    /// found: reachable blocks without a terminator, along with every violation reported by
+    /// `Function::verify_ssa_form`.
+    pub(crate) fn assert_well_formed(&self) -> Result<(), Vec<InternalError>> {
+        let cfg = ControlFlowGraph::with_function(self);
+        self.verify_ssa_form(&cfg)
+    }
+
+    /// Checks that every jmp passes the right number of arguments to its destination and that
+    /// every value is defined before it is used, returning every violation found.
+    ///
+    /// A value is defined before a use if it is defined earlier in the same block, or defined in
+    /// a block which dominates the block of the use. Only reachable blocks are checked, and any
+    /// without a terminator are reported rather than causing a panic.
+    pub(crate) fn verify_ssa_form(&self, cfg: &ControlFlowGraph) -> Result<(), Vec<InternalError>> {
+        let post_order = PostOrder::with_function(self);
+        let dom = DominatorTree::with_cfg_and_post_order(cfg, &post_order);
+
+        let mut blocks: Vec<_> = self.reachable_blocks().into_iter().collect();
+        blocks.sort();
+
+        let mut instruction_blocks = HashMap::new();
+        for block in &blocks {
+            for instruction in self.dfg[*block].instructions() {
+                instruction_blocks.insert(*instruction, *block);
+            }
+        }
+
+        let mut verifier = Verifier { dfg: &self.dfg, dom, instruction_blocks, errors: Vec::new() };
+        for block in blocks {
+            verifier.verify_block(block);
+        }
+
+        if verifier.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(verifier.errors)
+        }
+    }
+}
+
+/// Per function context for collecting SSA form violations.
+struct Verifier<'f> {
+    dfg: &'f DataFlowGraph,
+    dom: DominatorTree,
+
+    /// Maps each instruction within a reachable block to that block.
+    instruction_blocks: HashMap<InstructionId, BasicBlockId>,
+    errors: Vec<InternalError>,
+}
+
+impl<'f> Verifier<'f> {
+    fn verify_block(&mut self, block: BasicBlockId) {
+        // The instructions of this block which have been visited so far
+        let mut defined = HashSet::new();
+        let dfg = self.dfg;
+        let basic_block = &dfg[block];
+
+        for instruction in basic_block.instructions() {
+            dfg[*instruction].for_each_value(|value| self.verify_use(value, block, &defined));
+            defined.insert(*instruction);
+        }
+
+        let terminator = match basic_block.terminator() {
+            Some(terminator) => terminator,
+            None => {
+                self.errors.push(InternalError::MissingTerminator { block });
+                return;
+            }
+        };
+        terminator.for_each_value(|value| self.verify_use(value, block, &defined));
+
+        match terminator {
+            TerminatorInstruction::Jmp { destination, arguments } => {
+                self.verify_arguments(block, *destination, arguments.len());
+            }
+            TerminatorInstruction::JmpIf { then_destination, else_destination, .. } => {
+                self.verify_arguments(block, *then_destination, 0);
+                self.verify_arguments(block, *else_destination, 0);
+            }
+            TerminatorInstruction::Return { .. } => (),
+        }
+    }
+
+    /// Checks that `destination` expects exactly as many parameters as `block` passes to it.
+    fn verify_arguments(&mut self, block: BasicBlockId, destination: BasicBlockId, actual: usize) {
+        let expected = self.dfg[destination].parameters().len();
+        if expected != actual {
+            self.errors.push(InternalError::BlockArgumentMismatch {
+                block,
+                destination,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    /// Inspects a value recursively (as it could be an array) and checks that each comprised
+    /// instruction result or block parameter is defined before its use in `block`.
+    ///
+    /// `defined` holds the instructions preceding the use within `block`.
+    fn verify_use(
+        &mut self,
+        value_id: ValueId,
+        block: BasicBlockId,
+        defined: &HashSet<InstructionId>,
+    ) {
+        let dfg = self.dfg;
+        let value_id = dfg.resolve(value_id);
+        let is_defined = match &dfg[value_id] {
+            Value::Instruction { instruction, .. } => {
+                match self.instruction_blocks.get(instruction) {
+                    Some(defining_block) if *defining_block == block => {
+                        defined.contains(instruction)
+                    }
+                    Some(defining_block) => self.strictly_dominates(*defining_block, block),
+                    None => false,
+                }
+            }
+            Value::Param { block: defining_block, .. } => {
+                *defining_block == block || self.strictly_dominates(*defining_block, block)
+            }
+            Value::Array { array, .. } => {
+                for element in array {
+                    self.verify_use(*element, block, defined);
+                }
+                true
+            }
+            Value::NumericConstant { .. }
+            | Value::Function(_)
+            | Value::Intrinsic(_)
+            | Value::ForeignFunction(_) => true,
+        };
+
+        if !is_defined {
+            self.errors.push(InternalError::UseBeforeDefinition { value: value_id, block });
+        }
+    }
+
+    fn strictly_dominates(&self, a: BasicBlockId, b: BasicBlockId) -> bool {
+        a != b && self.dom.is_reachable(a) && self.dom.dominates_helper(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ssa_refactor::{
+        ir::{
+            cfg::ControlFlowGraph, errors::InternalError, function::RuntimeType,
+            instruction::BinaryOp, map::Id, types::Type,
+        },
+        ssa_builder::FunctionBuilder,
+    };
+
+    #[test]
+    fn reports_every_violation() {
+        // fn main {
+        //   b0(v0: u1, v1: Field):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     v2 = add v1, Field 1
+        //     jmp b3()
+        //   b2():
+        //     jmp b3(v2)
+        //   b3():
+        //     return v2
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        let one = builder.field_constant(1u128);
+        let v2 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b3, vec![]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_jmp(b3, vec![v2]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![v2]);
+
+        let ssa = builder.finish();
+        let main = ssa.main();
+        let cfg = ControlFlowGraph::with_function(main);
+
+        // v2 is defined in b1, which dominates neither b2 nor b3
+        let errors = main.verify_ssa_form(&cfg).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                InternalError::UseBeforeDefinition { value: v2, block: b2 },
+                InternalError::BlockArgumentMismatch {
+                    block: b2,
+                    destination: b3,
+                    expected: 0,
+                    actual: 1
+                },
+                InternalError::UseBeforeDefinition { value: v2, block: b3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn well_formed_reports_every_defect() {
+        // fn main {
+        //   b0(v0: u1, v1: Field):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     v2 = add v1, Field 1
+        //     jmp b3()
+        //   b2():
+        //     jmp b3(v2)
+        //   b3():
+        //     (no terminator)
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        let one = builder.field_constant(1u128);
+        let v2 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b3, vec![]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_jmp(b3, vec![v2]);
+
+        let ssa = builder.finish();
+        let main = ssa.main();
+
+        let errors = main.assert_well_formed().unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                InternalError::UseBeforeDefinition { value: v2, block: b2 },
+                InternalError::BlockArgumentMismatch {
+                    block: b2,
+                    destination: b3,
+                    expected: 0,
+                    actual: 1
+                },
+                InternalError::MissingTerminator { block: b3 },
+            ]
+        );
+    }
+}
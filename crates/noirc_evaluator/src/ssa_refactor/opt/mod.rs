@@ -10,4 +10,5 @@ mod flatten_cfg;
 mod inlining;
 mod mem2reg;
 mod simplify_cfg;
+mod tail_duplication;
 mod unrolling;
@@ -8,17 +8,176 @@
 //! 5. Replaces any jmpifs with constant conditions with jmps. If this causes the block to have
 //!    only 1 successor then (2) also will be applied.
 //!
-//! Currently, 1 and 4 are unimplemented.
-use std::collections::HashSet;
+//! Currently, 1 is unimplemented and 4 is only available separately through
+//! `Function::thread_trivial_jumps`.
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use crate::ssa_refactor::{
     ir::{
         basic_block::BasicBlockId, cfg::ControlFlowGraph, function::Function,
-        instruction::TerminatorInstruction,
+        instruction::TerminatorInstruction, value::ValueId,
     },
     ssa_gen::Ssa,
 };
 
+impl Function {
+    /// Thread jumps through forwarding blocks: blocks with no parameters, no instructions,
+    /// and a single unconditional jmp as their terminator. Each predecessor of a forwarding
+    /// block is rewired to jump directly to the forwarding block's destination, passing along
+    /// any arguments the forwarding jmp would have passed.
+    ///
+    /// Threaded blocks are left empty and unreachable, with their edges removed from `cfg`.
+    /// Returns the number of blocks threaded.
+    pub(crate) fn thread_trivial_jumps(&mut self, cfg: &mut ControlFlowGraph) -> usize {
+        let mut blocks: Vec<_> = self.reachable_blocks().into_iter().collect();
+        blocks.sort();
+
+        let mut threaded = 0;
+        for block in blocks {
+            if try_thread_block(self, cfg, block) {
+                threaded += 1;
+            }
+        }
+        threaded
+    }
+
+    /// Repeatedly merge each block into its sole predecessor whenever that predecessor has
+    /// no other successors, substituting the block's parameters with the predecessor's jmp
+    /// arguments.
+    ///
+    /// Merged blocks are left empty and unreachable, with their edges removed from `cfg`.
+    /// Returns the number of blocks merged.
+    pub(crate) fn merge_linear_blocks(&mut self, cfg: &mut ControlFlowGraph) -> usize {
+        let mut merged = 0;
+        loop {
+            let mut blocks: Vec<_> = self.reachable_blocks().into_iter().collect();
+            blocks.sort();
+
+            let merged_before = merged;
+            for block in blocks {
+                if try_merge_into_predecessor(self, cfg, block) {
+                    merged += 1;
+                }
+            }
+
+            if merged == merged_before {
+                return merged;
+            }
+        }
+    }
+
+    /// Replace each jmpif whose condition has a known value in `known` with a jmp to the
+    /// destination it would take, updating `cfg` to match.
+    ///
+    /// The arm no longer taken is left in place for a later pass to remove if it has become
+    /// unreachable. Returns the number of jmpifs folded.
+    pub(crate) fn fold_constant_branches(
+        &mut self,
+        known: &HashMap<ValueId, bool>,
+        cfg: &mut ControlFlowGraph,
+    ) -> usize {
+        let mut blocks: Vec<_> = self.reachable_blocks().into_iter().collect();
+        blocks.sort();
+
+        let mut folded = 0;
+        for block in blocks {
+            let destination = match self.dfg[block].terminator() {
+                Some(TerminatorInstruction::JmpIf {
+                    condition,
+                    then_destination,
+                    else_destination,
+                }) => match known.get(&self.dfg.resolve(*condition)) {
+                    Some(true) => *then_destination,
+                    Some(false) => *else_destination,
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            let jmp = TerminatorInstruction::Jmp { destination, arguments: Vec::new() };
+            self.dfg[block].set_terminator(jmp);
+            cfg.recompute_block(self, block);
+            folded += 1;
+        }
+        folded
+    }
+
+    /// Replace each jmpif which re-tests a condition already decided on the way into its block
+    /// with a jmp to the destination that condition forces, updating `cfg` to match.
+    ///
+    /// A condition is decided along an edge into a block when the predecessor ends in a jmpif on
+    /// the same condition, with exactly one of its arms targeting the block. A jmpif is only
+    /// replaced when every edge into its block decides the condition the same way. Returns the
+    /// number of jmpifs replaced.
+    pub(crate) fn simplify_redundant_conditions(&mut self, cfg: &mut ControlFlowGraph) -> usize {
+        let mut blocks: Vec<_> = self.reachable_blocks().into_iter().collect();
+        blocks.sort();
+
+        let mut simplified = 0;
+        for block in blocks {
+            let terminator = self.dfg[block].terminator();
+            let (condition, then_destination, else_destination) = match terminator {
+                Some(TerminatorInstruction::JmpIf {
+                    condition,
+                    then_destination,
+                    else_destination,
+                }) => (self.dfg.resolve(*condition), *then_destination, *else_destination),
+                _ => continue,
+            };
+
+            let decided: Vec<_> = cfg
+                .predecessors(block)
+                .map(|predecessor| decided_condition(self, predecessor, block, condition))
+                .collect();
+            let value = match decided.split_first() {
+                Some((Some(value), rest)) if rest.iter().all(|other| *other == Some(*value)) => {
+                    *value
+                }
+                _ => continue,
+            };
+
+            let destination = if value { then_destination } else { else_destination };
+            let jmp = TerminatorInstruction::Jmp { destination, arguments: Vec::new() };
+            self.dfg[block].set_terminator(jmp);
+            cfg.recompute_block(self, block);
+            simplified += 1;
+        }
+        simplified
+    }
+
+    /// Merge sibling blocks which are structurally identical: they share the same set of
+    /// predecessors, have parameters of the same types, and contain the same instructions and
+    /// terminator up to a renaming of the values they define. One block of each group is kept,
+    /// every edge into the others is rewired to it, and the values they defined are replaced
+    /// with the kept block's equivalents.
+    ///
+    /// Merged blocks are left empty and unreachable, with their edges removed from `cfg`.
+    /// Returns the number of blocks merged.
+    pub(crate) fn merge_identical_blocks(&mut self, cfg: &mut ControlFlowGraph) -> usize {
+        let mut blocks: Vec<_> = self.reachable_blocks().into_iter().collect();
+        blocks.sort();
+
+        let mut merged = 0;
+        let mut removed = HashSet::new();
+        for (i, &kept) in blocks.iter().enumerate() {
+            if removed.contains(&kept) {
+                continue;
+            }
+            for &block in &blocks[i + 1..] {
+                if removed.contains(&block) {
+                    continue;
+                }
+                if let Some(replacements) = identical_block_values(self, cfg, kept, block) {
+                    merge_block_into(self, cfg, block, kept, replacements);
+                    removed.insert(block);
+                    merged += 1;
+                }
+            }
+        }
+        merged
+    }
+}
+
 impl Ssa {
     /// Simplify each function's control flow graph by:
     /// 1. Removing blocks with no predecessors
@@ -146,10 +305,246 @@ fn try_inline_into_predecessor(
     }
 }
 
+/// Try to merge `block` into its sole predecessor, returning true if successful.
+///
+/// This only applies if the predecessor's only successor is `block`.
+fn try_merge_into_predecessor(
+    function: &mut Function,
+    cfg: &mut ControlFlowGraph,
+    block: BasicBlockId,
+) -> bool {
+    if block == function.entry_block() {
+        return false;
+    }
+
+    let mut predecessors = cfg.predecessors(block);
+    if predecessors.len() != 1 {
+        return false;
+    }
+    let predecessor = predecessors.next().expect("Already checked length of predecessors");
+    drop(predecessors);
+
+    // A block jumping to itself can't be merged, and checking the successor count up front
+    // avoids removing block parameters from a block we won't end up inlining.
+    if predecessor == block || cfg.successors(predecessor).len() != 1 {
+        return false;
+    }
+
+    remove_block_parameters(function, block, predecessor);
+    try_inline_into_predecessor(function, cfg, block, predecessor)
+}
+
+/// Try to rewire each predecessor of `block` to skip over it, returning true if successful.
+///
+/// This only applies if `block` is a forwarding block as described in
+/// `Function::thread_trivial_jumps`.
+fn try_thread_block(
+    function: &mut Function,
+    cfg: &mut ControlFlowGraph,
+    block: BasicBlockId,
+) -> bool {
+    let basic_block = &function.dfg[block];
+    if block == function.entry_block()
+        || !basic_block.parameters().is_empty()
+        || !basic_block.instructions().is_empty()
+    {
+        return false;
+    }
+
+    let (destination, arguments) = match basic_block.terminator() {
+        Some(TerminatorInstruction::Jmp { destination, arguments }) if *destination != block => {
+            (*destination, arguments.clone())
+        }
+        _ => return false,
+    };
+
+    let predecessors: Vec<_> = cfg.predecessors(block).collect();
+
+    // A jmpif cannot pass block arguments, so it can only skip the forwarding block if the
+    // forwarding jmp does not pass any either.
+    let has_jmpif_predecessor = predecessors.iter().any(|predecessor| {
+        matches!(function.dfg[*predecessor].terminator(), Some(TerminatorInstruction::JmpIf { .. }))
+    });
+    if has_jmpif_predecessor && !arguments.is_empty() {
+        return false;
+    }
+
+    // The control flow graph bounds how many predecessors a block may have
+    let mut new_predecessors: HashSet<_> = cfg.predecessors(destination).collect();
+    new_predecessors.remove(&block);
+    new_predecessors.extend(predecessors.iter().copied());
+    if new_predecessors.len() > cfg.max_predecessors() {
+        return false;
+    }
+
+    // Detach the forwarding block first so that `destination` never transiently has more
+    // predecessors than the control flow graph allows.
+    function.dfg[block].take_terminator();
+    cfg.recompute_block(function, block);
+
+    for predecessor in predecessors {
+        match function.dfg[predecessor].unwrap_terminator_mut() {
+            TerminatorInstruction::Jmp {
+                destination: jmp_destination,
+                arguments: jmp_arguments,
+            } => {
+                // The forwarding block has no parameters so the original jmp passed no arguments
+                *jmp_destination = destination;
+                *jmp_arguments = arguments.clone();
+            }
+            terminator @ TerminatorInstruction::JmpIf { .. } => {
+                terminator
+                    .mutate_blocks(|target| if target == block { destination } else { target });
+            }
+            TerminatorInstruction::Return { .. } => {
+                unreachable!("A predecessor of a block cannot end in a return")
+            }
+        }
+        cfg.recompute_block(function, predecessor);
+    }
+    true
+}
+
+/// If `block` is structurally identical to its sibling `kept`, returns the pairs of values
+/// (defined in `block`, equivalent value defined in `kept`) needed to replace one with the other.
+fn identical_block_values(
+    function: &Function,
+    cfg: &ControlFlowGraph,
+    kept: BasicBlockId,
+    block: BasicBlockId,
+) -> Option<Vec<(ValueId, ValueId)>> {
+    let entry_block = function.entry_block();
+    if kept == entry_block || block == entry_block {
+        return None;
+    }
+
+    let kept_predecessors: BTreeSet<_> = cfg.predecessors(kept).collect();
+    let predecessors: BTreeSet<_> = cfg.predecessors(block).collect();
+    // Blocks jumping to themselves or each other are left alone
+    if kept_predecessors != predecessors
+        || predecessors.contains(&kept)
+        || predecessors.contains(&block)
+    {
+        return None;
+    }
+
+    let dfg = &function.dfg;
+    let kept_block = &dfg[kept];
+    let basic_block = &dfg[block];
+    if kept_block.parameters().len() != basic_block.parameters().len()
+        || kept_block.instructions().len() != basic_block.instructions().len()
+    {
+        return None;
+    }
+
+    let mut replacements = HashMap::new();
+    for (&kept_param, &param) in kept_block.parameters().iter().zip(basic_block.parameters()) {
+        if dfg.type_of_value(kept_param) != dfg.type_of_value(param) {
+            return None;
+        }
+        replacements.insert(param, kept_param);
+    }
+
+    let map_value = |replacements: &HashMap<ValueId, ValueId>, value: ValueId| {
+        let value = dfg.resolve(value);
+        replacements.get(&value).copied().unwrap_or(value)
+    };
+
+    for (&kept_instruction, &instruction) in
+        kept_block.instructions().iter().zip(basic_block.instructions())
+    {
+        let mapped = dfg[instruction].map_values(|value| map_value(&replacements, value));
+        let kept_mapped = dfg[kept_instruction].map_values(|value| dfg.resolve(value));
+        if mapped != kept_mapped {
+            return None;
+        }
+
+        let kept_results = dfg.instruction_results(kept_instruction);
+        let results = dfg.instruction_results(instruction);
+        if kept_results.len() != results.len() {
+            return None;
+        }
+        for (&kept_result, &result) in kept_results.iter().zip(results) {
+            if dfg.type_of_value(kept_result) != dfg.type_of_value(result) {
+                return None;
+            }
+            replacements.insert(result, kept_result);
+        }
+    }
+
+    let terminator = basic_block.terminator()?.map_values(|value| map_value(&replacements, value));
+    let kept_terminator = kept_block.terminator()?.map_values(|value| dfg.resolve(value));
+    if terminator != kept_terminator {
+        return None;
+    }
+
+    Some(replacements.into_iter().collect())
+}
+
+/// Returns the value `condition` is known to have when control flows from `predecessor` to
+/// `block`: true if `predecessor` branches on it and only its then arm targets `block`, and
+/// false if only its else arm does.
+fn decided_condition(
+    function: &Function,
+    predecessor: BasicBlockId,
+    block: BasicBlockId,
+    condition: ValueId,
+) -> Option<bool> {
+    match function.dfg[predecessor].terminator() {
+        Some(TerminatorInstruction::JmpIf {
+            condition: predecessor_condition,
+            then_destination,
+            else_destination,
+        }) if function.dfg.resolve(*predecessor_condition) == condition => {
+            match (*then_destination == block, *else_destination == block) {
+                (true, false) => Some(true),
+                (false, true) => Some(false),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Rewire every predecessor of `block` to `kept` instead, replacing the values defined in
+/// `block` with their equivalents from `kept` and leaving `block` empty.
+fn merge_block_into(
+    function: &mut Function,
+    cfg: &mut ControlFlowGraph,
+    block: BasicBlockId,
+    kept: BasicBlockId,
+    replacements: Vec<(ValueId, ValueId)>,
+) {
+    let predecessors: Vec<_> = cfg.predecessors(block).collect();
+
+    function.dfg[block].instructions_mut().clear();
+    function.dfg[block].take_terminator();
+    cfg.recompute_block(function, block);
+
+    for predecessor in predecessors {
+        function.dfg[predecessor].unwrap_terminator_mut().mutate_blocks(|target| {
+            if target == block {
+                kept
+            } else {
+                target
+            }
+        });
+        cfg.recompute_block(function, predecessor);
+    }
+
+    // Blocks dominated by `block` may still refer to the values it defined
+    for (value, kept_value) in replacements {
+        function.dfg.set_value_from_id(value, kept_value);
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
     use crate::ssa_refactor::{
         ir::{
+            cfg::ControlFlowGraph,
             function::RuntimeType,
             instruction::{BinaryOp, TerminatorInstruction},
             map::Id,
@@ -268,4 +663,295 @@ mod test {
             other => panic!("Unexpected terminator {other:?}"),
         }
     }
+
+    #[test]
+    fn thread_forwarding_block() {
+        // fn main {
+        //   b0():
+        //     jmp b1()
+        //   b1():
+        //     jmp b2(Field 7)
+        //   b2(v0: Field):
+        //     return v0
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let v0 = builder.add_block_parameter(b2, Type::field());
+
+        let seven = builder.field_constant(7u128);
+        builder.terminate_with_jmp(b1, vec![]);
+
+        builder.switch_to_block(b1);
+        builder.terminate_with_jmp(b2, vec![seven]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![v0]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        let b0 = main.entry_block();
+        let mut cfg = ControlFlowGraph::with_function(main);
+
+        assert_eq!(main.thread_trivial_jumps(&mut cfg), 1);
+
+        // Expected output:
+        // fn main {
+        //   b0():
+        //     jmp b2(Field 7)
+        //   b2(v0: Field):
+        //     return v0
+        // }
+        let reachable = main.reachable_blocks();
+        assert_eq!(reachable.len(), 2);
+        assert!(!reachable.contains(&b1));
+
+        match main.dfg[b0].terminator() {
+            Some(TerminatorInstruction::Jmp { destination, arguments }) => {
+                assert_eq!(*destination, b2);
+                assert_eq!(arguments, &vec![seven]);
+            }
+            other => panic!("Unexpected terminator {other:?}"),
+        }
+
+        let b0_successors: Vec<_> = cfg.successors(b0).collect();
+        let b2_predecessors: Vec<_> = cfg.predecessors(b2).collect();
+        assert_eq!(b0_successors, vec![b2]);
+        assert_eq!(b2_predecessors, vec![b0]);
+        assert_eq!(cfg.predecessors(b1).len(), 0);
+        assert_eq!(cfg.successors(b1).len(), 0);
+    }
+
+    #[test]
+    fn merge_linear_chain() {
+        // fn main {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 1
+        //     jmp b1(v1)
+        //   b1(v2: Field):
+        //     v3 = mul v2, Field 2
+        //     jmp b2(v3)
+        //   b2(v4: Field):
+        //     return v4
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let v2 = builder.add_block_parameter(b1, Type::field());
+        let v4 = builder.add_block_parameter(b2, Type::field());
+
+        let one = builder.field_constant(1u128);
+        let two = builder.field_constant(2u128);
+
+        let v1 = builder.insert_binary(v0, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b1, vec![v1]);
+
+        builder.switch_to_block(b1);
+        let v3 = builder.insert_binary(v2, BinaryOp::Mul, two);
+        builder.terminate_with_jmp(b2, vec![v3]);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_return(vec![v4]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        let b0 = main.entry_block();
+        let mut cfg = ControlFlowGraph::with_function(main);
+
+        assert_eq!(main.merge_linear_blocks(&mut cfg), 2);
+
+        // Expected output:
+        // fn main {
+        //   b0(v0: Field):
+        //     v1 = add v0, Field 1
+        //     v3 = mul v1, Field 2
+        //     return v3
+        // }
+        assert_eq!(main.reachable_blocks().len(), 1);
+        assert_eq!(main.dfg[b0].instructions().len(), 2);
+        assert_eq!(cfg.successors(b0).len(), 0);
+
+        match main.dfg[b0].terminator() {
+            Some(TerminatorInstruction::Return { return_values }) => {
+                assert_eq!(return_values.len(), 1);
+                assert_eq!(main.dfg.resolve(return_values[0]), v3);
+            }
+            other => panic!("Unexpected terminator {other:?}"),
+        }
+    }
+
+    #[test]
+    fn merge_identical_siblings() {
+        // fn main {
+        //   b0(v0: u1, v1: Field):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     v2 = add v1, Field 1
+        //     jmp b3(v2)
+        //   b2():
+        //     v3 = add v1, Field 1
+        //     jmp b3(v3)
+        //   b3(v4: Field):
+        //     return v4
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let v4 = builder.add_block_parameter(b3, Type::field());
+
+        let one = builder.field_constant(1u128);
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        let v2 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b3, vec![v2]);
+
+        builder.switch_to_block(b2);
+        let v3 = builder.insert_binary(v1, BinaryOp::Add, one);
+        builder.terminate_with_jmp(b3, vec![v3]);
+
+        builder.switch_to_block(b3);
+        builder.terminate_with_return(vec![v4]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        let b0 = main.entry_block();
+        let mut cfg = ControlFlowGraph::with_function(main);
+
+        assert_eq!(main.merge_identical_blocks(&mut cfg), 1);
+
+        // Both branches of b0 now lead to b1, and b2 is unreachable
+        match main.dfg[b0].terminator() {
+            Some(TerminatorInstruction::JmpIf { then_destination, else_destination, .. }) => {
+                assert_eq!(*then_destination, b1);
+                assert_eq!(*else_destination, b1);
+            }
+            other => panic!("Unexpected terminator {other:?}"),
+        }
+        assert_eq!(main.reachable_blocks().len(), 3);
+        assert!(!main.reachable_blocks().contains(&b2));
+        assert_eq!(cfg.predecessors(b2).len(), 0);
+        assert_eq!(cfg.predecessors(b3).collect::<Vec<_>>(), vec![b1]);
+        assert_eq!(main.dfg.resolve(v3), v2);
+    }
+
+    #[test]
+    fn fold_known_branch() {
+        // fn main {
+        //   b0(v0: u1):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     return Field 1
+        //   b2():
+        //     return Field 2
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        let one = builder.field_constant(1u128);
+        builder.terminate_with_return(vec![one]);
+
+        builder.switch_to_block(b2);
+        let two = builder.field_constant(2u128);
+        builder.terminate_with_return(vec![two]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        let b0 = main.entry_block();
+        let mut cfg = ControlFlowGraph::with_function(main);
+
+        let known = HashMap::from([(v0, true)]);
+        assert_eq!(main.fold_constant_branches(&known, &mut cfg), 1);
+
+        match main.dfg[b0].terminator() {
+            Some(TerminatorInstruction::Jmp { destination, arguments }) => {
+                assert_eq!(*destination, b1);
+                assert!(arguments.is_empty());
+            }
+            other => panic!("Unexpected terminator {other:?}"),
+        }
+        assert_eq!(cfg.successors(b0).collect::<Vec<_>>(), vec![b1]);
+        assert_eq!(cfg.predecessors(b2).len(), 0);
+
+        // There are no jmpifs left to fold
+        assert_eq!(main.fold_constant_branches(&known, &mut cfg), 0);
+    }
+
+    #[test]
+    fn simplify_redundant_condition() {
+        // fn main {
+        //   b0(v0: u1):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     jmpif v0 then: b3, else: b4
+        //   b2():
+        //     jmpif v0 then: b3, else: b4
+        //   b3():
+        //     return Field 1
+        //   b4():
+        //     return Field 2
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let b4 = builder.insert_block();
+
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        builder.terminate_with_jmpif(v0, b3, b4);
+
+        builder.switch_to_block(b2);
+        builder.terminate_with_jmpif(v0, b3, b4);
+
+        builder.switch_to_block(b3);
+        let one = builder.field_constant(1u128);
+        builder.terminate_with_return(vec![one]);
+
+        builder.switch_to_block(b4);
+        let two = builder.field_constant(2u128);
+        builder.terminate_with_return(vec![two]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        let mut cfg = ControlFlowGraph::with_function(main);
+
+        // v0 is true within b1 and false within b2, so each inner branch has a single outcome
+        assert_eq!(main.simplify_redundant_conditions(&mut cfg), 2);
+
+        for (block, expected) in [(b1, b3), (b2, b4)] {
+            match main.dfg[block].terminator() {
+                Some(TerminatorInstruction::Jmp { destination, arguments }) => {
+                    assert_eq!(*destination, expected);
+                    assert!(arguments.is_empty());
+                }
+                other => panic!("Unexpected terminator {other:?}"),
+            }
+            assert_eq!(cfg.successors(block).collect::<Vec<_>>(), vec![expected]);
+        }
+
+        // The outer branch is still needed, so nothing further is simplified
+        assert_eq!(main.simplify_redundant_conditions(&mut cfg), 0);
+    }
 }
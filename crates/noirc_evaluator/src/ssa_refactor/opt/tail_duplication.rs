@@ -0,0 +1,198 @@
+//! Tail duplication copies a small join block into each of its predecessors, so that each copy
+//! only has a single predecessor. This can expose optimizations which were blocked by the join:
+//! once simplify cfg inlines each copy into its predecessor, any block arguments which were
+//! constant along that edge become constants within the copied instructions.
+use iter_extended::vecmap;
+
+use crate::ssa_refactor::ir::{
+    basic_block::BasicBlockId, cfg::ControlFlowGraph, dom::DominatorTree, function::Function,
+    function_inserter::FunctionInserter,
+};
+
+/// Blocks with more instructions than this are not duplicated, to limit code growth.
+const MAX_DUPLICATED_INSTRUCTIONS: usize = 8;
+
+impl Function {
+    /// Duplicate `block` once for each of its predecessors, rewiring each predecessor to jump to
+    /// its own copy instead. Returns the ids of the new blocks, ordered by the predecessor they
+    /// were created for.
+    ///
+    /// The original block is left empty and unreachable, with its edges removed from `cfg`.
+    ///
+    /// Nothing is duplicated, and an empty Vec returned, if `block` is the entry block, has
+    /// fewer than two predecessors, jumps to itself, has more than `MAX_DUPLICATED_INSTRUCTIONS`
+    /// instructions, or dominates another block (which may then use the values it defines).
+    pub(crate) fn tail_duplicate(
+        &mut self,
+        block: BasicBlockId,
+        cfg: &mut ControlFlowGraph,
+    ) -> Vec<BasicBlockId> {
+        let mut predecessors: Vec<_> = cfg.predecessors(block).collect();
+        predecessors.sort();
+
+        if block == self.entry_block()
+            || predecessors.len() < 2
+            || predecessors.contains(&block)
+            || self.dfg[block].instructions().len() > MAX_DUPLICATED_INSTRUCTIONS
+            || dominates_other_blocks(self, block)
+        {
+            return Vec::new();
+        }
+
+        let new_blocks = vecmap(predecessors, |predecessor| {
+            let new_block = copy_block(self, block);
+            self.dfg[predecessor].unwrap_terminator_mut().mutate_blocks(|target| {
+                if target == block {
+                    new_block
+                } else {
+                    target
+                }
+            });
+
+            // Recompute the predecessor first as this is what adds `new_block` to the cfg
+            cfg.recompute_block(self, predecessor);
+            cfg.recompute_block(self, new_block);
+            new_block
+        });
+
+        self.dfg[block].instructions_mut().clear();
+        self.dfg[block].take_terminator();
+        cfg.recompute_block(self, block);
+
+        new_blocks
+    }
+}
+
+/// Returns true if `block` is the immediate dominator of any block.
+fn dominates_other_blocks(function: &Function, block: BasicBlockId) -> bool {
+    let dom = DominatorTree::with_function(function);
+    function
+        .reachable_blocks()
+        .into_iter()
+        .any(|other| dom.immediate_dominator(other) == Some(block))
+}
+
+/// Creates a new block with the same parameters, instructions, terminator, and metadata as
+/// `block`.
+///
+/// Each instruction is re-inserted rather than shared, so that the copy has its own results.
+fn copy_block(function: &mut Function, block: BasicBlockId) -> BasicBlockId {
+    let mut inserter = FunctionInserter::new(function);
+    let new_block = inserter.function.dfg.make_block_with_parameters_from_block(block);
+    inserter.remember_block_params_from_block(block, new_block);
+
+    let metadata = inserter.function.dfg[block].metadata().clone();
+    for (key, value) in metadata {
+        inserter.function.dfg[new_block].set_meta(key, value);
+    }
+
+    let instructions = inserter.function.dfg[block].instructions().to_vec();
+    for instruction in instructions {
+        inserter.push_instruction(instruction, new_block);
+    }
+
+    let terminator = inserter.function.dfg[block]
+        .unwrap_terminator()
+        .clone()
+        .map_values(|value| inserter.resolve(value));
+    inserter.function.dfg.set_block_terminator(new_block, terminator);
+    new_block
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa_refactor::{
+        ir::{
+            cfg::ControlFlowGraph,
+            function::RuntimeType,
+            instruction::{BinaryOp, TerminatorInstruction},
+            map::Id,
+            types::Type,
+        },
+        ssa_builder::FunctionBuilder,
+    };
+
+    #[test]
+    fn duplicate_join_block() {
+        // fn main {
+        //   b0(v0: u1, v1: Field):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     jmp b3(Field 1)
+        //   b2():
+        //     jmp b3(Field 2)
+        //   b3(v2: Field):
+        //     v3 = add v2, v1
+        //     return v3
+        // }
+        let main_id = Id::test_new(0);
+        let mut builder = FunctionBuilder::new("main".into(), main_id, RuntimeType::Acir);
+        let v0 = builder.add_parameter(Type::bool());
+        let v1 = builder.add_parameter(Type::field());
+
+        let b1 = builder.insert_block();
+        let b2 = builder.insert_block();
+        let b3 = builder.insert_block();
+        let v2 = builder.add_block_parameter(b3, Type::field());
+
+        builder.terminate_with_jmpif(v0, b1, b2);
+
+        builder.switch_to_block(b1);
+        let one = builder.field_constant(1u128);
+        builder.terminate_with_jmp(b3, vec![one]);
+
+        builder.switch_to_block(b2);
+        let two = builder.field_constant(2u128);
+        builder.terminate_with_jmp(b3, vec![two]);
+
+        builder.switch_to_block(b3);
+        let v3 = builder.insert_binary(v2, BinaryOp::Add, v1);
+        builder.terminate_with_return(vec![v3]);
+
+        let mut ssa = builder.finish();
+        let main = ssa.main_mut();
+        main.dfg[b3].set_meta("cold", "true");
+        let mut cfg = ControlFlowGraph::with_function(main);
+
+        // Expected output:
+        // fn main {
+        //   b0(v0: u1, v1: Field):
+        //     jmpif v0 then: b1, else: b2
+        //   b1():
+        //     jmp b4(Field 1)
+        //   b2():
+        //     jmp b5(Field 2)
+        //   b4(v4: Field):
+        //     v5 = add v4, v1
+        //     return v5
+        //   b5(v6: Field):
+        //     v7 = add v6, v1
+        //     return v7
+        // }
+        let new_blocks = main.tail_duplicate(b3, &mut cfg);
+        assert_eq!(new_blocks.len(), 2);
+
+        for (predecessor, new_block) in [b1, b2].into_iter().zip(new_blocks) {
+            match main.dfg[predecessor].terminator() {
+                Some(TerminatorInstruction::Jmp { destination, .. }) => {
+                    assert_eq!(*destination, new_block);
+                }
+                other => panic!("Expected a jmp to the duplicated block, got {other:?}"),
+            }
+            assert_eq!(cfg.predecessors(new_block).collect::<Vec<_>>(), vec![predecessor]);
+
+            let copy = &main.dfg[new_block];
+            assert_eq!(copy.get_meta("cold"), Some("true"));
+            assert_eq!(copy.parameters().len(), 1);
+            assert_eq!(copy.instructions().len(), 1);
+            let results = main.dfg.instruction_results(copy.instructions()[0]);
+            assert_eq!(
+                copy.terminator(),
+                Some(&TerminatorInstruction::Return { return_values: results.to_vec() })
+            );
+        }
+
+        assert!(!main.reachable_blocks().contains(&b3));
+        assert_eq!(cfg.predecessors(b3).len(), 0);
+    }
+}
@@ -14,11 +14,14 @@
 //! program that will need to be removed by a later simplify cfg pass.
 use std::collections::{HashMap, HashSet};
 
+use iter_extended::vecmap;
+
 use crate::ssa_refactor::{
     ir::{
         basic_block::BasicBlockId, cfg::ControlFlowGraph, dfg::DataFlowGraph, dom::DominatorTree,
         function::Function, function_inserter::FunctionInserter,
-        instruction::TerminatorInstruction, post_order::PostOrder, value::ValueId,
+        instruction::TerminatorInstruction, loops::NaturalLoop, post_order::PostOrder,
+        value::ValueId,
     },
     ssa_gen::Ssa,
 };
@@ -34,25 +37,12 @@ impl Ssa {
     }
 }
 
-struct Loop {
-    /// The header block of a loop is the block which dominates all the
-    /// other blocks in the loop.
-    header: BasicBlockId,
-
-    /// The start of the back_edge n -> d is the block n at the end of
-    /// the loop that jumps back to the header block d which restarts the loop.
-    back_edge_start: BasicBlockId,
-
-    /// All the blocks contained within the loop, including `header` and `back_edge_start`.
-    blocks: HashSet<BasicBlockId>,
-}
-
 struct Loops {
     /// The loops that failed to be unrolled so that we do not try to unroll them again.
     /// Each loop is identified by its header block id.
     failed_to_unroll: HashSet<BasicBlockId>,
 
-    yet_to_unroll: Vec<Loop>,
+    yet_to_unroll: Vec<NaturalLoop>,
     modified_blocks: HashSet<BasicBlockId>,
     cfg: ControlFlowGraph,
     dom_tree: DominatorTree,
@@ -63,26 +53,17 @@ struct Loops {
 fn find_all_loops(function: &Function) -> Loops {
     let cfg = ControlFlowGraph::with_function(function);
     let post_order = PostOrder::with_function(function);
-    let mut dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
-
-    let mut loops = vec![];
+    let dom_tree = DominatorTree::with_cfg_and_post_order(&cfg, &post_order);
 
-    for (block, _) in function.dfg.basic_blocks_iter() {
-        // These reachable checks wouldn't be needed if we only iterated over reachable blocks
-        if dom_tree.is_reachable(block) {
-            for predecessor in cfg.predecessors(block) {
-                if dom_tree.is_reachable(predecessor) && dom_tree.dominates(block, predecessor) {
-                    // predecessor -> block is the back-edge of a loop
-                    loops.push(find_blocks_in_loop(block, predecessor, &cfg));
-                }
-            }
-        }
-    }
+    // Each back-edge `predecessor -> block` marks a loop with `block` as its header
+    let mut loops = vecmap(cfg.back_edges(&dom_tree), |(predecessor, block)| {
+        NaturalLoop::with_back_edge(block, predecessor, &cfg)
+    });
 
     // Sort loops by block size so that we unroll the larger, outer loops of nested loops first.
     // This is needed because inner loops may use the induction variable from their outer loops in
     // their loop range.
-    loops.sort_by_key(|loop_| loop_.blocks.len());
+    loops.sort_by_key(|loop_| loop_.blocks().len());
 
     Loops {
         failed_to_unroll: HashSet::new(),
@@ -100,59 +81,33 @@ impl Loops {
         while let Some(next_loop) = self.yet_to_unroll.pop() {
             // If we've previously modified a block in this loop we need to refresh the context.
             // This happens any time we have nested loops.
-            if next_loop.blocks.iter().any(|block| self.modified_blocks.contains(block)) {
+            if next_loop.blocks().iter().any(|block| self.modified_blocks.contains(block)) {
                 let mut new_context = find_all_loops(function);
                 new_context.failed_to_unroll = self.failed_to_unroll;
                 return new_context.unroll_each_loop(function);
             }
 
             // Don't try to unroll the loop again if it is known to fail
-            if !self.failed_to_unroll.contains(&next_loop.header) {
+            if !self.failed_to_unroll.contains(&next_loop.header()) {
                 if unroll_loop(function, &self.cfg, &next_loop).is_ok() {
-                    self.modified_blocks.extend(next_loop.blocks);
+                    self.modified_blocks.extend(next_loop.blocks());
                 } else {
-                    self.failed_to_unroll.insert(next_loop.header);
+                    self.failed_to_unroll.insert(next_loop.header());
                 }
             }
         }
     }
 }
 
-/// Return each block that is in a loop starting in the given header block.
-/// Expects back_edge_start -> header to be the back edge of the loop.
-fn find_blocks_in_loop(
-    header: BasicBlockId,
-    back_edge_start: BasicBlockId,
-    cfg: &ControlFlowGraph,
-) -> Loop {
-    let mut blocks = HashSet::new();
-    blocks.insert(header);
-
-    let mut insert = |block, stack: &mut Vec<BasicBlockId>| {
-        if !blocks.contains(&block) {
-            blocks.insert(block);
-            stack.push(block);
-        }
-    };
-
-    // Starting from the back edge of the loop, each predecessor of this block until
-    // the header is within the loop.
-    let mut stack = vec![];
-    insert(back_edge_start, &mut stack);
-
-    while let Some(block) = stack.pop() {
-        for predecessor in cfg.predecessors(block) {
-            insert(predecessor, &mut stack);
-        }
-    }
-
-    Loop { header, back_edge_start, blocks }
-}
-
 /// Unroll a single loop in the function.
 /// Returns Err(()) if it failed to unroll and Ok(()) otherwise.
-fn unroll_loop(function: &mut Function, cfg: &ControlFlowGraph, loop_: &Loop) -> Result<(), ()> {
-    let mut unroll_into = get_pre_header(cfg, loop_);
+fn unroll_loop(
+    function: &mut Function,
+    cfg: &ControlFlowGraph,
+    loop_: &NaturalLoop,
+) -> Result<(), ()> {
+    let mut unroll_into =
+        loop_.preheader(cfg).expect("Expected loop header to have a single pre-header");
     let mut jump_value = get_induction_variable(function, unroll_into)?;
 
     while let Some(context) = unroll_loop_header(function, loop_, unroll_into, jump_value)? {
@@ -164,19 +119,6 @@ fn unroll_loop(function: &mut Function, cfg: &ControlFlowGraph, loop_: &Loop) ->
     Ok(())
 }
 
-/// The loop pre-header is the block that comes before the loop begins. Generally a header block
-/// is expected to have 2 predecessors: the pre-header and the final block of the loop which jumps
-/// back to the beginning.
-fn get_pre_header(cfg: &ControlFlowGraph, loop_: &Loop) -> BasicBlockId {
-    let mut pre_header = cfg
-        .predecessors(loop_.header)
-        .filter(|predecessor| *predecessor != loop_.back_edge_start)
-        .collect::<Vec<_>>();
-
-    assert_eq!(pre_header.len(), 1);
-    pre_header.remove(0)
-}
-
 /// Return the induction value of the current iteration of the loop, from the given block's jmp arguments.
 ///
 /// Expects the current block to terminate in `jmp h(N)` where h is the loop header and N is
@@ -205,7 +147,7 @@ fn get_induction_variable(function: &Function, block: BasicBlockId) -> Result<Va
 /// Returns Some(iteration context) if we should perform another iteration.
 fn unroll_loop_header<'a>(
     function: &'a mut Function,
-    loop_: &'a Loop,
+    loop_: &'a NaturalLoop,
     unroll_into: BasicBlockId,
     induction_value: ValueId,
 ) -> Result<Option<LoopIteration<'a>>, ()> {
@@ -214,7 +156,7 @@ fn unroll_loop_header<'a>(
     // just discard this fresh block and leave the loop unmodified.
     let fresh_block = function.dfg.make_block();
 
-    let mut context = LoopIteration::new(function, loop_, fresh_block, loop_.header);
+    let mut context = LoopIteration::new(function, loop_, fresh_block, loop_.header());
     let source_block = &context.dfg()[context.source_block];
     assert_eq!(source_block.parameters().len(), 1, "Expected only 1 argument in loop header");
 
@@ -236,7 +178,7 @@ fn unroll_loop_header<'a>(
                 // unroll_into block from now on.
                 context.insert_block = unroll_into;
 
-                Ok(loop_.blocks.contains(&context.source_block).then_some(context))
+                Ok(loop_.contains(context.source_block).then_some(context))
             } else {
                 // If this case is reached the loop either uses non-constant indices or we need
                 // another pass, such as mem2reg to resolve them to constants.
@@ -251,7 +193,7 @@ fn unroll_loop_header<'a>(
 /// Notably each loop iteration maps each loop block to a fresh, unrolled block.
 struct LoopIteration<'f> {
     inserter: FunctionInserter<'f>,
-    loop_: &'f Loop,
+    loop_: &'f NaturalLoop,
 
     /// Maps pre-unrolled block ids from within the loop to new block ids of each loop
     /// block for each loop iteration.
@@ -274,7 +216,7 @@ struct LoopIteration<'f> {
 impl<'f> LoopIteration<'f> {
     fn new(
         function: &'f mut Function,
-        loop_: &'f Loop,
+        loop_: &'f NaturalLoop,
         insert_block: BasicBlockId,
         source_block: BasicBlockId,
     ) -> Self {
@@ -318,7 +260,7 @@ impl<'f> LoopIteration<'f> {
         let mut next_blocks = self.unroll_loop_block_helper();
         next_blocks.retain(|block| {
             let b = self.get_original_block(*block);
-            self.loop_.blocks.contains(&b)
+            self.loop_.contains(b)
         });
         next_blocks
     }
@@ -333,7 +275,7 @@ impl<'f> LoopIteration<'f> {
                 self.handle_jmpif(*condition, *then_destination, *else_destination)
             }
             TerminatorInstruction::Jmp { destination, arguments } => {
-                if self.get_original_block(*destination) == self.loop_.header {
+                if self.get_original_block(*destination) == self.loop_.header() {
                     assert_eq!(arguments.len(), 1);
                     self.induction_value = Some((self.insert_block, arguments[0]));
                 }
@@ -377,7 +319,7 @@ impl<'f> LoopIteration<'f> {
         }
 
         // If the block is in the loop we create a fresh block for each iteration
-        if self.loop_.blocks.contains(&block) {
+        if self.loop_.contains(block) {
             let new_block = self.dfg_mut().make_block_with_parameters_from_block(block);
             self.inserter.remember_block_params_from_block(block, new_block);
 